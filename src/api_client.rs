@@ -1,590 +1,229 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashSet};
 
-use comfy_table::{ContentArrangement, Table};
+use futures::{StreamExt, TryStreamExt};
 use reqwest::Method;
-use serde_json::{json, Value};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 
-use crate::interaction::InteractionTrait;
-use crate::{datetime, http};
+use crate::http;
+use crate::models::{ClusterNode, Func, Info, Job, Queue, Record, RecordPage};
 
+/// Client for the AGScheduler HTTP API, every method returns the typed `data` of the response.
 pub struct AGScheduler {
-    pub endpoint: String,
+    pub client: http::Client,
 }
 
 impl AGScheduler {
-    async fn _edit_job(
-        &self,
-        data: HashMap<&str, String>,
-        method: Method,
-        interaction: &dyn InteractionTrait,
-    ) {
-        let name = interaction.input_name(data.get("name").unwrap());
-        let _type = interaction.select_type().to_lowercase();
-
-        let mut start_at = String::new();
-        let mut interval = String::new();
-        let mut cron_expr = String::new();
-        match _type.as_str() {
-            "datetime" => {
-                start_at = interaction.input_start_at(data.get("start_at").unwrap());
-            }
-            "interval" => {
-                interval = interaction.input_interval(data.get("interval").unwrap());
-            }
-            "cron" => {
-                cron_expr = interaction.input_cron_expr(data.get("cron_expr").unwrap());
-            }
-            _ => {}
-        }
-
-        let mut tz = iana_time_zone::get_timezone().unwrap();
-        if !data.get("timezone").unwrap().is_empty() {
-            tz = data.get("timezone").unwrap().to_string();
-        }
-        let timezone = interaction.input_timezone(&tz);
+    pub fn new(config: http::Config) -> anyhow::Result<Self> {
+        Ok(AGScheduler {
+            client: http::Client::new(config)?,
+        })
+    }
 
-        let mut fn_selections: Vec<String> = vec![];
-        match http::fetch(
-            format!("{}{}", &self.endpoint, "/funcs"),
-            http::Options::default(),
-        )
-        .await
-        {
-            Ok(result) => {
-                if let Value::Array(list) = result {
-                    for f in list {
-                        let f_name = f["name"].as_str().unwrap().to_string();
-                        fn_selections.push(f_name);
-                    }
-                }
-            }
-            Err(err) => {
-                println!("Error: {}", err);
-                return;
-            }
-        }
-        let func_name = interaction.select_func_name(fn_selections);
-
-        let args = interaction.input_args(data.get("args").unwrap());
-        let timeout = interaction.input_timeout(data.get("timeout").unwrap());
-        let queues = interaction.input_queues(data.get("queues").unwrap());
-
-        let args_value: Value = serde_json::from_str(&args).unwrap();
-        let queues_value: Value = serde_json::from_str(&queues).unwrap();
-        let body = json!(
-            {
-                "id": data.get("id").unwrap(),
-                "name": name,
-                "type": _type,
-                "start_at": start_at,
-                "interval": interval,
-                "cron_expr": cron_expr,
-                "timezone": timezone,
-                "func_name": func_name,
-                "args": args_value,
-                "timeout": timeout,
-                "queues": queues_value,
-            }
-        );
-        http::fetch_show_json(
-            format!("{}{}", &self.endpoint, "/scheduler/job"),
-            http::Options {
-                method,
-                body: body.to_string(),
-                ..Default::default()
-            },
-        )
-        .await;
+    async fn _fetch(&self, url_path: &str, options: http::Options) -> anyhow::Result<Value> {
+        self.client.fetch(url_path, options).await
     }
 
-    pub async fn add_job(&self, interaction: &dyn InteractionTrait) {
-        let mut data = HashMap::new();
-        for key in [
-            "id",
-            "name",
-            "type",
-            "start_at",
-            "interval",
-            "cron_expr",
-            "timezone",
-            "func_name",
-            "args",
-            "timeout",
-            "queues",
-        ] {
-            data.insert(key, "".to_string());
-        }
+    async fn _get<T: DeserializeOwned>(&self, url_path: &str) -> anyhow::Result<T> {
+        let result = self._fetch(url_path, http::Options::default()).await?;
 
-        self._edit_job(data, Method::POST, interaction).await;
+        Ok(serde_json::from_value(result)?)
     }
 
-    pub async fn update_job(&self, interaction: &dyn InteractionTrait) {
-        let id = interaction.input_id();
+    async fn _send(&self, method: Method, url_path: &str, body: String) -> anyhow::Result<Value> {
+        self._fetch(url_path, http::Options { method, body }).await
+    }
 
-        let mut data = HashMap::new();
-        match http::fetch(
-            format!("{}{}/{}", &self.endpoint, "/scheduler/job", id),
-            http::Options::default(),
-        )
-        .await
-        {
-            Ok(result) => {
-                data.insert("id", id);
-                data.insert("args", result["args"].to_string());
-                data.insert("queues", result["queues"].to_string());
-
-                for key in [
-                    "name",
-                    "type",
-                    "start_at",
-                    "interval",
-                    "cron_expr",
-                    "timezone",
-                    "func_name",
-                    "timeout",
-                ] {
-                    data.insert(key, result[key].as_str().unwrap().to_string());
-                }
-            }
-            Err(err) => {
-                println!("Error: {}", err);
-                return;
-            }
+    fn _records_path(job_id: &str) -> String {
+        let mut url_path = String::from("/recorder/records");
+        if !job_id.is_empty() {
+            url_path = format!("{}/{}", url_path, job_id);
         }
 
-        self._edit_job(data, Method::PUT, interaction).await;
+        url_path
     }
 
-    pub async fn get_job(&self, interaction: &dyn InteractionTrait) {
-        let id = interaction.input_id();
+    pub async fn add_job(&self, job: &Job) -> anyhow::Result<Job> {
+        let result = self
+            ._send(Method::POST, "/scheduler/job", job.to_body().to_string())
+            .await?;
 
-        http::fetch_show_json(
-            format!("{}{}/{}", &self.endpoint, "/scheduler/job", id),
-            http::Options::default(),
-        )
-        .await;
+        Ok(serde_json::from_value(result)?)
     }
 
-    pub async fn get_all_jobs(&self) {
-        match http::fetch(
-            format!("{}{}", &self.endpoint, "/scheduler/jobs"),
-            http::Options::default(),
-        )
-        .await
-        {
-            Ok(result) => {
-                let mut table = Table::new();
-                table
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec![
-                        "ID",
-                        "Name",
-                        "Type",
-                        "TypeValue",
-                        "LastRunTime",
-                        "NextRunTime",
-                        "Status",
-                    ]);
-
-                if let Value::Array(list) = result {
-                    let total = list.len();
-                    for j in list {
-                        let _type = j["type"].as_str().unwrap();
-                        let mut type_value = "";
-                        match _type {
-                            "datetime" => {
-                                type_value = j["start_at"].as_str().unwrap();
-                            }
-                            "interval" => {
-                                type_value = j["interval"].as_str().unwrap();
-                            }
-                            "cron" => {
-                                type_value = j["cron_expr"].as_str().unwrap();
-                            }
-                            _ => {}
-                        }
-                        let last_run_time =
-                            datetime::parse_iso8601_to_local(j["last_run_time"].as_str().unwrap())
-                                .unwrap()
-                                .format("%Y-%m-%d %H:%M:%S")
-                                .to_string();
-                        let next_run_time =
-                            datetime::parse_iso8601_to_local(j["next_run_time"].as_str().unwrap())
-                                .unwrap()
-                                .format("%Y-%m-%d %H:%M:%S")
-                                .to_string();
-                        table.add_row(vec![
-                            j["id"].as_str().unwrap(),
-                            j["name"].as_str().unwrap(),
-                            _type,
-                            type_value,
-                            &last_run_time[..],
-                            &next_run_time[..],
-                            j["status"].as_str().unwrap(),
-                        ]);
-                    }
-
-                    println!("{table}");
-                    println!("Total {}", total);
-                }
-            }
-            Err(err) => {
-                println!("Error: {}", err)
-            }
-        }
+    pub async fn update_job(&self, job: &Job) -> anyhow::Result<Job> {
+        let result = self
+            ._send(Method::PUT, "/scheduler/job", job.to_body().to_string())
+            .await?;
+
+        Ok(serde_json::from_value(result)?)
     }
 
-    pub async fn delete_job(&self, interaction: &dyn InteractionTrait) {
-        let id = interaction.input_id();
+    pub async fn get_job(&self, id: &str) -> anyhow::Result<Job> {
+        self._get(&format!("/scheduler/job/{}", id)).await
+    }
 
-        if !interaction.confirm_delete() {
-            return;
-        }
+    pub async fn list_jobs(&self) -> anyhow::Result<Vec<Job>> {
+        self._get("/scheduler/jobs").await
+    }
 
-        http::fetch_show_ok(
-            format!("{}{}/{id}", &self.endpoint, "/scheduler/job"),
-            http::Options {
-                method: Method::DELETE,
-                ..Default::default()
-            },
+    pub async fn delete_job(&self, id: &str) -> anyhow::Result<()> {
+        self._send(
+            Method::DELETE,
+            &format!("/scheduler/job/{}", id),
+            String::new(),
         )
-        .await;
+        .await?;
+
+        Ok(())
     }
 
-    pub async fn delete_all_jobs(&self, interaction: &dyn InteractionTrait) {
-        if !interaction.confirm_delete() {
-            return;
-        }
+    pub async fn delete_all_jobs(&self) -> anyhow::Result<()> {
+        self._send(Method::DELETE, "/scheduler/jobs", String::new())
+            .await?;
 
-        http::fetch_show_ok(
-            format!("{}{}", &self.endpoint, "/scheduler/jobs"),
-            http::Options {
-                method: Method::DELETE,
-                ..Default::default()
-            },
-        )
-        .await;
+        Ok(())
     }
 
-    pub async fn pause_or_resume_job(&self, action: &str, interaction: &dyn InteractionTrait) {
-        let id = interaction.input_id();
-
-        http::fetch_show_ok(
-            format!("{}{}/{}/{}", &self.endpoint, "/scheduler/job", id, action),
-            http::Options {
-                method: Method::POST,
-                ..Default::default()
-            },
+    pub async fn pause_job(&self, id: &str) -> anyhow::Result<()> {
+        self._send(
+            Method::POST,
+            &format!("/scheduler/job/{}/pause", id),
+            String::new(),
         )
-        .await;
-    }
+        .await?;
 
-    pub async fn run_or_schedule_job(&self, action: &str, interaction: &dyn InteractionTrait) {
-        let id = interaction.input_id();
+        Ok(())
+    }
 
-        match http::fetch(
-            format!("{}{}/{}", &self.endpoint, "/scheduler/job", id),
-            http::Options::default(),
+    pub async fn resume_job(&self, id: &str) -> anyhow::Result<()> {
+        self._send(
+            Method::POST,
+            &format!("/scheduler/job/{}/resume", id),
+            String::new(),
         )
-        .await
-        {
-            Ok(result) => {
-                let args = result["args"].to_string();
-                let args_value: Value = serde_json::from_str(&args).unwrap();
-                let queues = result["queues"].to_string();
-                let queues_value: Value = serde_json::from_str(&queues).unwrap();
-                let body = json!(
-                    {
-                        "id": id,
-                        "name": result["name"].as_str().unwrap().to_string(),
-                        "type": result["type"].as_str().unwrap().to_string(),
-                        "start_at": result["start_at"].as_str().unwrap().to_string(),
-                        "interval": result["interval"].as_str().unwrap().to_string(),
-                        "cron_expr": result["cron_expr"].as_str().unwrap().to_string(),
-                        "timezone": result["timezone"].as_str().unwrap().to_string(),
-                        "func_name": result["func_name"].as_str().unwrap().to_string(),
-                        "args": args_value,
-                        "timeout": result["timeout"].as_str().unwrap().to_string(),
-                        "queues": queues_value,
-                    }
-                );
-                http::fetch_show_ok(
-                    format!("{}{}/{}", &self.endpoint, "/scheduler/job", action),
-                    http::Options {
-                        method: Method::POST,
-                        body: body.to_string(),
-                        ..Default::default()
-                    },
-                )
-                .await;
-            }
-            Err(err) => {
-                println!("Error: {}", err);
-            }
-        }
+        .await?;
+
+        Ok(())
     }
 
-    pub async fn start_or_stop(&self, action: &str) {
-        http::fetch_show_ok(
-            format!("{}{}/{}", &self.endpoint, "/scheduler", action),
-            http::Options {
-                method: Method::POST,
-                ..Default::default()
-            },
+    /// Runs the job once in the foreground, without changing its schedule.
+    pub async fn run_job(&self, job: &Job) -> anyhow::Result<()> {
+        self._send(
+            Method::POST,
+            "/scheduler/job/run",
+            job.to_body().to_string(),
         )
-        .await;
-    }
+        .await?;
 
-    async fn _get_records(&self, job_id: &str, interaction: &dyn InteractionTrait) {
-        let page = interaction.input_page("");
-        let page_size = interaction.input_page_size("");
+        Ok(())
+    }
 
-        let mut url_path = String::from("/recorder/records");
-        if !job_id.is_empty() {
-            url_path = format!("{}/{}", url_path, job_id);
-        }
-        let query: String = format!("page={}&page_size={}", page, page_size);
-        url_path = format!("{}?{}", url_path, query);
-        match http::fetch(
-            format!("{}{}", &self.endpoint, url_path),
-            http::Options::default(),
+    /// Schedules the job once in the background, without changing its schedule.
+    pub async fn schedule_job(&self, job: &Job) -> anyhow::Result<()> {
+        self._send(
+            Method::POST,
+            "/scheduler/job/schedule",
+            job.to_body().to_string(),
         )
-        .await
-        {
-            Ok(result) => {
-                let mut table = Table::new();
-                table
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec![
-                        "ID", "JobName", "JobId", "Status", "StartAt", "EndAt", "Result",
-                    ]);
-
-                if let Value::Object(map) = result {
-                    if let Value::Array(list) = &map["res"] {
-                        for r in list {
-                            let start_at =
-                                datetime::parse_iso8601_to_local(r["start_at"].as_str().unwrap())
-                                    .unwrap()
-                                    .format("%Y-%m-%d %H:%M:%S")
-                                    .to_string();
-                            let mut end_at = String::from("");
-                            if r["status"] != "running" {
-                                end_at =
-                                    datetime::parse_iso8601_to_local(r["end_at"].as_str().unwrap())
-                                        .unwrap()
-                                        .format("%Y-%m-%d %H:%M:%S")
-                                        .to_string();
-                            }
-                            table.add_row(vec![
-                                &r["id"].to_string(),
-                                r["job_name"].as_str().unwrap(),
-                                r["job_id"].as_str().unwrap(),
-                                r["status"].as_str().unwrap(),
-                                &start_at[..],
-                                &end_at[..],
-                                r["result"].as_str().unwrap(),
-                            ]);
-                        }
+        .await?;
 
-                        println!("{table}");
-                    }
-
-                    let page = map["page"].to_string().parse::<f32>().unwrap();
-                    let page_size = map["page_size"].to_string().parse::<f32>().unwrap();
-                    let total = map["total"].to_string().parse::<f32>().unwrap();
-                    let page_count = total / page_size;
-                    println!(
-                        "Page {}/{}  PageSize {}  Total {}",
-                        page,
-                        page_count.ceil(),
-                        page_size,
-                        total
-                    );
-                }
-            }
-            Err(err) => {
-                println!("Error: {}", err)
-            }
-        }
+        Ok(())
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        self._send(Method::POST, "/scheduler/start", String::new())
+            .await?;
+
+        Ok(())
     }
 
-    pub async fn get_records(&self, interaction: &dyn InteractionTrait) {
-        let job_id = interaction.input_job_id();
-        self._get_records(&job_id, interaction).await;
+    pub async fn stop(&self) -> anyhow::Result<()> {
+        self._send(Method::POST, "/scheduler/stop", String::new())
+            .await?;
+
+        Ok(())
     }
 
-    pub async fn get_all_records(&self, interaction: &dyn InteractionTrait) {
-        self._get_records("", interaction).await;
+    /// Lists one page of records, of a single job if `job_id` is not empty.
+    pub async fn list_records(
+        &self,
+        job_id: &str,
+        page: u64,
+        page_size: u64,
+    ) -> anyhow::Result<RecordPage> {
+        self._get(&format!(
+            "{}?page={}&page_size={}",
+            Self::_records_path(job_id),
+            page,
+            page_size
+        ))
+        .await
     }
 
-    async fn _delete_records(&self, job_id: &str, interaction: &dyn InteractionTrait) {
-        if !interaction.confirm_delete() {
-            return;
+    /// Fetches every page of records in order, at most `concurrency` pages at a time,
+    /// passing each page to `on_page` until it returns false. Returns the total on the server.
+    ///
+    /// Records already seen on an earlier page are skipped, pages shift when records are added meanwhile.
+    pub async fn walk_records<F>(
+        &self,
+        job_id: &str,
+        page_size: u64,
+        concurrency: usize,
+        mut on_page: F,
+    ) -> anyhow::Result<u64>
+    where
+        F: FnMut(Vec<Record>) -> anyhow::Result<bool>,
+    {
+        let first = self.list_records(job_id, 1, page_size).await?;
+        let total = first.total;
+        let page_count = first.page_count();
+
+        let mut seen = HashSet::new();
+        let mut unseen = |records: Vec<Record>| -> Vec<Record> {
+            records.into_iter().filter(|r| seen.insert(r.id)).collect()
+        };
+        if !on_page(unseen(first.res))? {
+            return Ok(total);
         }
 
-        let mut url_path = String::from("/recorder/records");
-        if !job_id.is_empty() {
-            url_path = format!("{}/{}", url_path, job_id);
+        let mut pages = futures::stream::iter(2..=page_count)
+            .map(|page| self.list_records(job_id, page, page_size))
+            .buffered(concurrency.max(1));
+        while let Some(page) = pages.try_next().await? {
+            if !on_page(unseen(page.res))? {
+                break;
+            }
         }
-        http::fetch_show_ok(
-            format!("{}{}", &self.endpoint, url_path),
-            http::Options {
-                method: Method::DELETE,
-                ..Default::default()
-            },
-        )
-        .await;
-    }
 
-    pub async fn delete_records(&self, interaction: &dyn InteractionTrait) {
-        let job_id = interaction.input_job_id();
-        self._delete_records(&job_id, interaction).await;
+        Ok(total)
     }
 
-    pub async fn delete_all_records(&self, interaction: &dyn InteractionTrait) {
-        self._delete_records("", interaction).await;
+    /// Deletes the records of a single job, or all records if `job_id` is empty.
+    pub async fn delete_records(&self, job_id: &str) -> anyhow::Result<()> {
+        self._send(Method::DELETE, &Self::_records_path(job_id), String::new())
+            .await?;
+
+        Ok(())
     }
 
-    pub async fn get_info(&self) {
-        http::fetch_show_json(
-            format!("{}{}", &self.endpoint, "/info"),
-            http::Options::default(),
-        )
-        .await;
+    pub async fn get_info(&self) -> anyhow::Result<Info> {
+        self._get("/info").await
     }
 
-    pub async fn get_funcs(&self) {
-        match http::fetch(
-            format!("{}{}", &self.endpoint, "/funcs"),
-            http::Options::default(),
-        )
-        .await
-        {
-            Ok(result) => {
-                let mut table = Table::new();
-                table
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec!["name", "info"]);
-
-                if let Value::Array(list) = result {
-                    let total = list.len();
-                    for f in list {
-                        table.add_row(vec![
-                            f["name"].as_str().unwrap(),
-                            f["info"].as_str().unwrap(),
-                        ]);
-                    }
-
-                    println!("{table}");
-                    println!("Total {}", total);
-                }
-            }
-            Err(err) => {
-                println!("Error: {}", err)
-            }
-        }
+    pub async fn list_funcs(&self) -> anyhow::Result<Vec<Func>> {
+        self._get("/funcs").await
     }
 
-    pub async fn get_queues(&self) {
-        match http::fetch(
-            format!("{}{}", &self.endpoint, "/broker/queues"),
-            http::Options::default(),
-        )
-        .await
-        {
-            Ok(result) => {
-                let mut table = Table::new();
-                table
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec!["Name", "Type", "Count", "Workers"]);
-
-                if let Value::Array(list) = result {
-                    let total = list.len();
-                    for q in list {
-                        table.add_row(vec![
-                            q["name"].as_str().unwrap(),
-                            q["type"].as_str().unwrap(),
-                            &q["count"].to_string(),
-                            &q["workers"].to_string(),
-                        ]);
-                    }
-
-                    println!("{table}");
-                    println!("Total {}", total);
-                }
-            }
-            Err(err) => {
-                println!("Error: {}", err)
-            }
-        }
+    pub async fn list_queues(&self) -> anyhow::Result<Vec<Queue>> {
+        self._get("/broker/queues").await
     }
 
-    pub async fn get_cluster_nodes(&self) {
-        match http::fetch(
-            format!("{}{}", &self.endpoint, "/cluster/nodes"),
-            http::Options::default(),
-        )
-        .await
-        {
-            Ok(result) => {
-                let mut table = Table::new();
-                table
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec![
-                        "Endpoint",
-                        "Leader",
-                        "EndpointGRPC",
-                        "EndpointHTTP",
-                        "EndpointMain",
-                        "Queue",
-                        "Mode",
-                        "Version",
-                        "Health",
-                        "RegisterTime",
-                        "LastHeartbeatTime",
-                    ]);
-
-                if let Value::Object(map) = result {
-                    let total = map.len();
-                    for (_, n) in map.iter() {
-                        let mut is_leader = false;
-                        if n["endpoint"] == n["endpoint_main"] {
-                            is_leader = true;
-                        }
-                        let register_time =
-                            datetime::parse_iso8601_to_local(n["register_time"].as_str().unwrap())
-                                .unwrap()
-                                .format("%Y-%m-%d %H:%M:%S")
-                                .to_string();
-                        let last_heartbeat_time = datetime::parse_iso8601_to_local(
-                            n["last_heartbeat_time"].as_str().unwrap(),
-                        )
-                        .unwrap()
-                        .format("%Y-%m-%d %H:%M:%S")
-                        .to_string();
-                        table.add_row(vec![
-                            n["endpoint"].as_str().unwrap(),
-                            &is_leader.to_string(),
-                            n["endpoint_grpc"].as_str().unwrap(),
-                            n["endpoint_http"].as_str().unwrap(),
-                            n["endpoint_main"].as_str().unwrap(),
-                            n["queue"].as_str().unwrap(),
-                            n["mode"].as_str().unwrap(),
-                            n["version"].as_str().unwrap(),
-                            &n["health"].as_bool().unwrap().to_string()[..],
-                            &register_time[..],
-                            &last_heartbeat_time[..],
-                        ]);
-                    }
-
-                    println!("{table}");
-                    println!("Total {}", total);
-                }
-            }
-            Err(err) => {
-                println!("Error: {}", err)
-            }
-        }
+    /// Lists cluster nodes, sorted by endpoint.
+    pub async fn list_cluster_nodes(&self) -> anyhow::Result<Vec<ClusterNode>> {
+        let nodes: BTreeMap<String, ClusterNode> = self._get("/cluster/nodes").await?;
+
+        Ok(nodes.into_values().collect())
     }
 }
 
@@ -593,235 +232,72 @@ mod tests {
     use super::*;
     use serde_json::json;
 
-    use crate::interaction::MockInteractionTrait;
-
     #[tokio::test]
     async fn it_api_client() {
         let mut server = mockito::Server::new_async().await;
         let url = server.url();
 
-        let id = String::from("00227fbf671f4ed2");
-        let job_id = String::from("b1638cfb7a8d4247");
+        let id = "00227fbf671f4ed2";
         let empty_data = json!({"data": null, "error": ""}).to_string();
-
-        let page = String::from("1");
-        let page_size = String::from("10");
-
-        server
-            .mock("POST", "/scheduler/job")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("GET", format!("/scheduler/job/{}", id).as_str())
-            .with_status(200)
-            .with_body(
-                json!({
-                    "data":  {
-                        "args": {
-
-                        },
-                        "cron_expr": "",
-                        "end_at": "",
-                        "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
-                        "id": "00227fbf671f4ed2",
-                        "interval": "60s",
-                        "last_run_time": "0001-01-01T00:00:00Z",
-                        "name": "myJob",
-                        "next_run_time": "2024-04-15T04:19:12Z",
-                        "queues": [],
-                        "start_at": "",
-                        "status": "running",
-                        "timeout": "1h",
-                        "timezone": "UTC",
-                        "type": "interval"
-                    },
-                    "error": ""
-                })
-                .to_string(),
-            )
-            .create_async()
-            .await;
+        let job_value = json!({
+            "args": {},
+            "cron_expr": "",
+            "end_at": "",
+            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
+            "id": id,
+            "interval": "60s",
+            "last_run_time": "0001-01-01T00:00:00Z",
+            "name": "myJob",
+            "next_run_time": "2024-04-15T04:19:12Z",
+            "queues": [],
+            "start_at": "",
+            "status": "running",
+            "timeout": "1h",
+            "timezone": "UTC",
+            "type": "interval"
+        });
+        let job_data = json!({"data": job_value, "error": ""}).to_string();
+        let job: Job = serde_json::from_value(job_value.clone()).unwrap();
+
+        let mut mocks = vec![];
+        for (method, path, body) in [
+            ("POST", "/scheduler/job".to_string(), &job_data),
+            ("PUT", "/scheduler/job".to_string(), &job_data),
+            ("GET", format!("/scheduler/job/{}", id), &job_data),
+            ("DELETE", format!("/scheduler/job/{}", id), &empty_data),
+            ("DELETE", "/scheduler/jobs".to_string(), &empty_data),
+            ("POST", format!("/scheduler/job/{}/pause", id), &empty_data),
+            ("POST", format!("/scheduler/job/{}/resume", id), &empty_data),
+            ("POST", "/scheduler/job/run".to_string(), &empty_data),
+            ("POST", "/scheduler/job/schedule".to_string(), &empty_data),
+            ("POST", "/scheduler/start".to_string(), &empty_data),
+            ("POST", "/scheduler/stop".to_string(), &empty_data),
+            ("DELETE", format!("/recorder/records/{}", id), &empty_data),
+            ("DELETE", "/recorder/records".to_string(), &empty_data),
+        ] {
+            mocks.push(
+                server
+                    .mock(method, path.as_str())
+                    .with_status(200)
+                    .with_body(body)
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
         server
             .mock("GET", "/scheduler/jobs")
-            .with_status(200)
-            .with_body(
-                json!({
-                    "data": [
-                        {
-                            "args": {
-
-                            },
-                            "cron_expr": "",
-                            "end_at": "",
-                            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
-                            "id": "00227fbf671f4ed2",
-                            "interval": "60s",
-                            "last_run_time": "0001-01-01T00:00:00Z",
-                            "name": "myJob",
-                            "next_run_time": "2024-04-15T04:19:12Z",
-                            "queues": [],
-                            "start_at": "",
-                            "status": "running",
-                            "timeout": "1h",
-                            "timezone": "UTC",
-                            "type": "interval"
-                        },
-                        {
-                            "args": {
-
-                            },
-                            "cron_expr": "",
-                            "end_at": "",
-                            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
-                            "id": "5hy65y56yh65y56h",
-                            "interval": "",
-                            "last_run_time": "0001-01-01T00:00:00Z",
-                            "name": "myJob2",
-                            "next_run_time": "2024-04-15T04:19:12Z",
-                            "queues": [],
-                            "start_at": "2024-05-16 17:16:08",
-                            "status": "running",
-                            "timeout": "1h",
-                            "timezone": "UTC",
-                            "type": "datetime"
-                        },
-                        {
-                            "args": {
-
-                            },
-                            "cron_expr": "*/1 * * * *",
-                            "end_at": "",
-                            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
-                            "id": "n4yhb56j3gj45h56",
-                            "interval": "",
-                            "last_run_time": "0001-01-01T00:00:00Z",
-                            "name": "myJob3",
-                            "next_run_time": "2024-04-15T04:19:12Z",
-                            "queues": [],
-                            "start_at": "",
-                            "status": "running",
-                            "timeout": "1h",
-                            "timezone": "UTC",
-                            "type": "cron"
-                        }
-                    ],
-                    "error": ""
-                })
-                .to_string(),
-            )
-            .create_async()
-            .await;
-        server
-            .mock("PUT", "/scheduler/job")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("DELETE", format!("/scheduler/job/{}", id).as_str())
-            .with_status(200)
-            .with_body(&empty_data)
+            .with_body(json!({"data": [job_value], "error": ""}).to_string())
             .create_async()
             .await;
         server
-            .mock("DELETE", "/scheduler/jobs")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("POST", format!("/scheduler/job/{}/pause", id).as_str())
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("POST", format!("/scheduler/job/{}/resume", id).as_str())
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("POST", "/scheduler/job/run")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("POST", "/scheduler/job/schedule")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("POST", "/scheduler/start")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("POST", "/scheduler/stop")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock(
-                "GET",
-                format!(
-                    "/recorder/records/{}?page={}&page_size={}",
-                    job_id, page, page_size
-                )
-                .as_str(),
-            )
-            .with_status(200)
+            .mock("GET", "/recorder/records?page=2&page_size=1")
             .with_body(
                 json!({
                     "data": {
-                        "page": 1,
-                        "page_size": 10,
+                        "page": 2,
+                        "page_size": 1,
                         "res": [
-                            {
-                                "id": 516544388,
-                                "job_id": "b1638cfb7a8d4247",
-                                "job_name": "myJob5",
-                                "status": "completed",
-                                "result": "",
-                                "start_at": "2024-06-03T11:27:28.002Z",
-                                "end_at": "2024-06-03T11:27:28.027Z"
-                            }
-                        ],
-                        "total": 1
-                    },
-                    "error": ""
-                })
-                .to_string(),
-            )
-            .create_async()
-            .await;
-        server
-            .mock(
-                "GET",
-                format!("/recorder/records?page={}&page_size={}", page, page_size).as_str(),
-            )
-            .with_status(200)
-            .with_body(
-                json!({
-                    "data": {
-                        "page": 1,
-                        "page_size": 10,
-                        "res": [
-                            {
-                                "id": 516544388,
-                                "job_id": "b1638cfb7a8d4247",
-                                "job_name": "myJob5",
-                                "status": "completed",
-                                "result": "",
-                                "start_at": "2024-06-03T11:27:28.002Z",
-                                "end_at": "2024-06-03T11:27:28.027Z"
-                            },
                             {
                                 "id": 516541097,
                                 "job_id": "e99532afe9f44e63",
@@ -840,33 +316,14 @@ mod tests {
             )
             .create_async()
             .await;
-        server
-            .mock("DELETE", format!("/recorder/records/{}", job_id).as_str())
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
-        server
-            .mock("DELETE", "/recorder/records")
-            .with_status(200)
-            .with_body(&empty_data)
-            .create_async()
-            .await;
         server
             .mock("GET", "/info")
-            .with_status(200)
             .with_body(
                 json!({
                     "data": {
-                        "cluster_main_node": {
-                            "endpoint": "127.0.0.1:36380",
-                            "endpoint_grpc": "127.0.0.1:36360",
-                            "endpoint_http": "127.0.0.1:36370",
-                            "endpoint_main": "127.0.0.1:36380",
-                            "mode": ""
-                        },
-                        "is_cluster_mode": true,
-                        "is_running": false,
+                        "cluster_main_node": null,
+                        "is_cluster_mode": false,
+                        "is_running": true,
                         "version": "0.6.1"
                     },
                     "error": ""
@@ -877,7 +334,6 @@ mod tests {
             .await;
         server
             .mock("GET", "/funcs")
-            .with_status(200)
             .with_body(
                 json!({
                     "data": [
@@ -894,17 +350,9 @@ mod tests {
             .await;
         server
             .mock("GET", "/broker/queues")
-            .with_status(200)
             .with_body(
                 json!({
-                    "data": [
-                        {
-                            "name": "default",
-                            "type": "Memory",
-                            "count": 1,
-                            "workers": 2,
-                        },
-                    ],
+                    "data": [{"name": "default", "type": "Memory", "count": 1, "workers": 2}],
                     "error": ""
                 })
                 .to_string(),
@@ -913,21 +361,16 @@ mod tests {
             .await;
         server
             .mock("GET", "/cluster/nodes")
-            .with_status(200)
             .with_body(
                 json!({
                     "data": {
+                        "127.0.0.1:36381": {
+                            "endpoint": "127.0.0.1:36381",
+                            "endpoint_main": "127.0.0.1:36380"
+                        },
                         "127.0.0.1:36380": {
                             "endpoint": "127.0.0.1:36380",
-                            "endpoint_grpc": "127.0.0.1:36360",
-                            "endpoint_http": "127.0.0.1:36370",
-                            "endpoint_main": "127.0.0.1:36380",
-                            "health": true,
-                            "last_heartbeat_time": "2024-04-15T04:30:08.489043439Z",
-                            "mode": "",
-                            "queue": "default",
-                            "register_time": "2024-04-15T04:08:10.438222846Z",
-                            "version": "0.6.1"
+                            "endpoint_main": "127.0.0.1:36380"
                         }
                     },
                     "error": ""
@@ -937,46 +380,111 @@ mod tests {
             .create_async()
             .await;
 
-        let mut mock = MockInteractionTrait::new();
-        mock.expect_input_id().return_const(id);
-        mock.expect_input_job_id().return_const(job_id);
-        mock.expect_confirm_delete().return_const(true);
-        mock.expect_input_name().return_const("myJob");
-        mock.expect_input_start_at()
-            .return_const("2024-04-16 15:23:51");
-        mock.expect_input_interval().return_const("60s");
-        mock.expect_input_cron_expr().return_const("*/1 * * * *");
-        mock.expect_input_timezone().return_const("UTC");
-        mock.expect_input_args().return_const("{}");
-        mock.expect_input_timeout().return_const("1h");
-        mock.expect_input_queues().return_const("[]");
-        mock.expect_input_page().return_const(page);
-        mock.expect_input_page_size().return_const(page_size);
-        mock.expect_select_type().return_const("Interval");
-        mock.expect_select_func_name()
-            .return_const("github.com/agscheduler/agscheduler/examples.PrintMsg");
-
-        let ags = AGScheduler { endpoint: url };
-
-        ags.add_job(&mock).await;
-        ags.get_job(&mock).await;
-        ags.get_all_jobs().await;
-        ags.update_job(&mock).await;
-        ags.delete_job(&mock).await;
-        ags.delete_all_jobs(&mock).await;
-        ags.pause_or_resume_job("pause", &mock).await;
-        ags.pause_or_resume_job("resume", &mock).await;
-        ags.run_or_schedule_job("run", &mock).await;
-        ags.run_or_schedule_job("schedule", &mock).await;
-        ags.start_or_stop("start").await;
-        ags.start_or_stop("stop").await;
-        ags.get_records(&mock).await;
-        ags.get_all_records(&mock).await;
-        ags.delete_records(&mock).await;
-        ags.delete_all_records(&mock).await;
-        ags.get_info().await;
-        ags.get_funcs().await;
-        ags.get_queues().await;
-        ags.get_cluster_nodes().await;
+        let ags = AGScheduler::new(http::Config::new(&url)).unwrap();
+
+        assert_eq!(job, ags.add_job(&job).await.unwrap());
+        assert_eq!(job, ags.update_job(&job).await.unwrap());
+        assert_eq!(job, ags.get_job(id).await.unwrap());
+        assert_eq!(vec![job.clone()], ags.list_jobs().await.unwrap());
+        ags.delete_job(id).await.unwrap();
+        ags.delete_all_jobs().await.unwrap();
+        ags.pause_job(id).await.unwrap();
+        ags.resume_job(id).await.unwrap();
+        ags.run_job(&job).await.unwrap();
+        ags.schedule_job(&job).await.unwrap();
+        ags.start().await.unwrap();
+        ags.stop().await.unwrap();
+        ags.delete_records(id).await.unwrap();
+        ags.delete_records("").await.unwrap();
+
+        let records = ags.list_records("", 2, 1).await.unwrap();
+        assert_eq!(2, records.page_count());
+        assert_eq!("error", records.res[0].status);
+
+        let info = ags.get_info().await.unwrap();
+        assert!(info.is_running);
+        assert_eq!(None, info.cluster_main_node);
+
+        assert_eq!(1, ags.list_funcs().await.unwrap().len());
+        assert_eq!(2, ags.list_queues().await.unwrap()[0].workers);
+
+        let nodes = ags.list_cluster_nodes().await.unwrap();
+        assert_eq!("127.0.0.1:36380", nodes[0].endpoint);
+        assert!(nodes[0].is_leader());
+        assert!(!nodes[1].is_leader());
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn it_walk_records() {
+        let mut server = mockito::Server::new_async().await;
+
+        for (page, ids) in [(1, vec![1, 2]), (2, vec![2, 3]), (3, vec![4])] {
+            let res: Vec<Value> = ids.iter().map(|id| json!({"id": id})).collect();
+            server
+                .mock(
+                    "GET",
+                    format!("/recorder/records/x?page={page}&page_size=2").as_str(),
+                )
+                .with_body(
+                    json!({
+                        "data": {"page": page, "page_size": 2, "total": 5, "res": res},
+                        "error": ""
+                    })
+                    .to_string(),
+                )
+                .create_async()
+                .await;
+        }
+
+        let ags = AGScheduler::new(http::Config::new(&server.url())).unwrap();
+
+        let mut ids = vec![];
+        let total = ags
+            .walk_records("x", 2, 2, |records| {
+                ids.extend(records.iter().map(|r| r.id));
+                Ok(true)
+            })
+            .await
+            .unwrap();
+        assert_eq!(5, total);
+        assert_eq!(vec![1, 2, 3, 4], ids);
+
+        let mut pages = 0;
+        ags.walk_records("x", 2, 1, |_| {
+            pages += 1;
+            Ok(pages < 2)
+        })
+        .await
+        .unwrap();
+        assert_eq!(2, pages);
+    }
+
+    #[tokio::test]
+    async fn it_api_client_error() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        server
+            .mock("GET", "/scheduler/job/x")
+            .with_body(json!({"data": null, "error": "`id` not found!"}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/scheduler/jobs")
+            .with_body(json!({"data": {"unexpected": true}, "error": ""}).to_string())
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&url)).unwrap();
+
+        assert_eq!(
+            "`id` not found!",
+            ags.get_job("x").await.unwrap_err().to_string()
+        );
+        assert!(ags.list_jobs().await.is_err());
     }
 }
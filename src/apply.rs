@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::models::Job;
+use crate::validate;
+
+/// A job as declared in a spec file, the fields built by the interactive job editor.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobSpec {
+    /// Only needed to rename a job, jobs are matched by name otherwise.
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub job_type: Option<String>,
+    pub start_at: Option<String>,
+    pub interval: Option<String>,
+    pub cron_expr: Option<String>,
+    pub timezone: Option<String>,
+    pub func_name: String,
+    pub args: Map<String, Value>,
+    pub timeout: Option<String>,
+    pub queues: Vec<String>,
+}
+
+impl JobSpec {
+    /// Builds the job, filling unset fields with the same defaults as `job add`,
+    /// except the timezone which is UTC so that the plan does not depend on the machine.
+    pub fn to_job(&self) -> anyhow::Result<Job> {
+        if self.name.is_empty() {
+            return Err(anyhow::anyhow!("`name` is required"));
+        }
+        if self.func_name.is_empty() {
+            return Err(anyhow::anyhow!(
+                "`func_name` of `{}` is required",
+                self.name
+            ));
+        }
+        let _type = self.job_type.clone().unwrap_or("interval".to_string());
+
+        let mut start_at = String::new();
+        let mut interval = String::new();
+        let mut cron_expr = String::new();
+        match _type.as_str() {
+            "datetime" => {
+                start_at = self
+                    .start_at
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("`start_at` of `{}` is required", self.name))?;
+            }
+            "interval" => {
+                interval = self.interval.clone().unwrap_or("60s".to_string());
+            }
+            "cron" => {
+                cron_expr = self.cron_expr.clone().unwrap_or("*/1 * * * *".to_string());
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "`type` of `{}` must be datetime, interval or cron",
+                    self.name
+                ))
+            }
+        }
+
+        let timezone = self.timezone.clone().unwrap_or("UTC".to_string());
+
+        let job = Job {
+            id: self.id.clone().unwrap_or_default(),
+            name: self.name.to_string(),
+            job_type: _type,
+            start_at,
+            interval,
+            cron_expr,
+            timezone,
+            func_name: self.func_name.to_string(),
+            args: self.args.clone(),
+            timeout: self.timeout.clone().unwrap_or("1h".to_string()),
+            queues: self.queues.clone(),
+            ..Default::default()
+        };
+        validate::job(&job).map_err(|err| anyhow::anyhow!("`{}`: {err}", self.name))?;
+
+        Ok(job)
+    }
+}
+
+/// Parses a YAML or JSON spec file, either a list of jobs or a mapping with a `jobs` list.
+pub fn parse_specs(text: &str) -> anyhow::Result<Vec<JobSpec>> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(text)?;
+    if let Some(jobs) = value.get_mut("jobs") {
+        value = std::mem::take(jobs);
+    }
+
+    Ok(serde_yaml::from_value(value)?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// One step of a plan, `job` is the job to send, with the ID of the matched job when updating.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Change {
+    pub action: Action,
+    pub job: Job,
+    /// Fields differing from the existing job.
+    pub fields: Vec<String>,
+}
+
+/// Matches specs to existing jobs, by ID if set and by name otherwise.
+pub fn plan(specs: &[JobSpec], existing: &[Job]) -> anyhow::Result<Vec<Change>> {
+    let mut by_name: BTreeMap<&str, Vec<&Job>> = BTreeMap::new();
+    for job in existing {
+        by_name.entry(&job.name).or_default().push(job);
+    }
+
+    let mut seen = HashSet::new();
+    let mut changes = vec![];
+    for spec in specs {
+        let key = spec.id.clone().unwrap_or(spec.name.to_string());
+        if !seen.insert(key.to_string()) {
+            return Err(anyhow::anyhow!("`{key}` is declared more than once"));
+        }
+
+        let mut job = spec.to_job()?;
+        let current = match &spec.id {
+            Some(id) => Some(
+                existing
+                    .iter()
+                    .find(|j| &j.id == id)
+                    .ok_or_else(|| anyhow::anyhow!("Job `{id}` not found"))?,
+            ),
+            None => match by_name.get(spec.name.as_str()).map(|jobs| jobs.as_slice()) {
+                Some([job]) => Some(*job),
+                Some([_, _, ..]) => {
+                    return Err(anyhow::anyhow!(
+                        "Several jobs are named `{}`, set the `id` of the spec",
+                        spec.name
+                    ))
+                }
+                _ => None,
+            },
+        };
+
+        let change = match current {
+            None => Change {
+                action: Action::Create,
+                job,
+                fields: vec![],
+            },
+            Some(current) => {
+                job.id = current.id.to_string();
+                if spec.timezone.is_none() {
+                    job.timezone = current.timezone.to_string();
+                }
+                let fields = changed_fields(current, &job);
+                Change {
+                    action: if fields.is_empty() {
+                        Action::Unchanged
+                    } else {
+                        Action::Update
+                    },
+                    job,
+                    fields,
+                }
+            }
+        };
+        changes.push(change);
+    }
+
+    Ok(changes)
+}
+
+/// Returns the names of the fields of the job body that differ.
+pub fn changed_fields(old: &Job, new: &Job) -> Vec<String> {
+    let (Value::Object(old), Value::Object(new)) = (old.to_body(), new.to_body()) else {
+        return vec![];
+    };
+
+    new.iter()
+        .filter(|(key, value)| old.get(*key) != Some(value))
+        .map(|(key, _)| key.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn job(value: Value) -> Job {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn it_parse_specs() {
+        let yaml = r#"
+jobs:
+  - name: myJob
+    type: cron
+    cron_expr: "*/5 * * * *"
+    timezone: UTC
+    func_name: github.com/agscheduler/agscheduler/examples.PrintMsg
+    args: {arg1: "1"}
+"#;
+        let specs = parse_specs(yaml).unwrap();
+        let job = specs[0].to_job().unwrap();
+        assert_eq!("*/5 * * * *", job.cron_expr);
+        assert_eq!("", job.interval);
+        assert_eq!("1h", job.timeout);
+        assert_eq!(json!("1"), job.args["arg1"]);
+
+        let specs = parse_specs(r#"[{"name": "a", "func_name": "f", "timezone": "UTC"}]"#).unwrap();
+        assert_eq!("60s", specs[0].to_job().unwrap().interval);
+
+        assert!(parse_specs("- name: a\n  fn_name: f\n").is_err());
+        assert!(parse_specs("- name: a\n").unwrap()[0].to_job().is_err());
+        assert!(
+            parse_specs("- name: a\n  func_name: f\n  type: datetime\n").unwrap()[0]
+                .to_job()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn it_plan() {
+        let existing = vec![
+            job(json!({
+                "id": "1", "name": "same", "type": "interval", "interval": "60s",
+                "timezone": "UTC", "func_name": "f", "timeout": "1h", "status": "running"
+            })),
+            job(json!({
+                "id": "2", "name": "changed", "type": "interval", "interval": "60s",
+                "timezone": "UTC", "func_name": "f", "timeout": "1h"
+            })),
+            job(json!({
+                "id": "5", "name": "shanghai", "type": "interval", "interval": "60s",
+                "timezone": "Asia/Shanghai", "func_name": "f", "timeout": "1h"
+            })),
+            job(json!({"id": "3", "name": "dup"})),
+            job(json!({"id": "4", "name": "dup"})),
+        ];
+        let specs = parse_specs(
+            r#"
+- {name: same, func_name: f, timezone: UTC}
+- {name: changed, func_name: f, timezone: UTC, interval: 30s, args: {a: 1}}
+- {name: new, func_name: f, timezone: UTC}
+- {id: "4", name: renamed, func_name: f, timezone: UTC}
+- {name: shanghai, func_name: f}
+- {name: new-no-tz, func_name: f}
+"#,
+        )
+        .unwrap();
+
+        let changes = plan(&specs, &existing).unwrap();
+        assert_eq!(Action::Unchanged, changes[0].action);
+        assert_eq!(Action::Update, changes[1].action);
+        assert_eq!("2", changes[1].job.id);
+        assert_eq!(vec!["args", "interval"], changes[1].fields);
+        assert_eq!(Action::Create, changes[2].action);
+        assert_eq!("", changes[2].job.id);
+        assert_eq!(Action::Update, changes[3].action);
+        assert_eq!("4", changes[3].job.id);
+        assert_eq!(Action::Unchanged, changes[4].action);
+        assert_eq!("Asia/Shanghai", changes[4].job.timezone);
+        assert_eq!("UTC", changes[5].job.timezone);
+
+        let dup = parse_specs("- {name: dup, func_name: f}").unwrap();
+        assert!(plan(&dup, &existing).is_err());
+        let twice = parse_specs("- {name: a, func_name: f}\n- {name: a, func_name: f}").unwrap();
+        assert!(plan(&twice, &existing).is_err());
+        let missing = parse_specs("- {id: x, name: a, func_name: f}").unwrap();
+        assert!(plan(&missing, &existing).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use futures::StreamExt;
+
+use crate::api_client::AGScheduler;
+use crate::filter::JobFilter;
+use crate::models::Job;
+use crate::output::JobResult;
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum BulkAction {
+    Pause,
+    Resume,
+    Delete,
+    Run,
+}
+
+impl BulkAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BulkAction::Pause => "pause",
+            BulkAction::Resume => "resume",
+            BulkAction::Delete => "delete",
+            BulkAction::Run => "run",
+        }
+    }
+}
+
+/// Parses IDs separated by whitespace or commas, like the output of `jq -r .id`.
+pub fn parse_ids(text: &str) -> HashSet<String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_string())
+        .collect()
+}
+
+/// Selects the jobs matching `filter` and, if given, listed in `ids`,
+/// along with the listed IDs of no job, sorted.
+pub fn select(
+    jobs: Vec<Job>,
+    filter: &JobFilter,
+    ids: Option<&HashSet<String>>,
+    now: DateTime<Utc>,
+) -> (Vec<Job>, Vec<String>) {
+    let mut missing: Vec<String> = ids
+        .into_iter()
+        .flatten()
+        .filter(|id| !jobs.iter().any(|j| &j.id == *id))
+        .cloned()
+        .collect();
+    missing.sort();
+
+    let jobs = jobs
+        .into_iter()
+        .filter(|j| ids.is_none_or(|ids| ids.contains(&j.id)) && filter.matches(j, now))
+        .collect();
+
+    (jobs, missing)
+}
+
+/// Results of the listed IDs of no job.
+pub fn not_found(ids: Vec<String>) -> Vec<JobResult> {
+    ids.into_iter()
+        .map(|id| JobResult {
+            id,
+            name: String::new(),
+            result: "not found".to_string(),
+        })
+        .collect()
+}
+
+/// Applies `action` to every job, at most `concurrency` at a time, keeping the order of `jobs`.
+pub async fn apply(
+    ags: &AGScheduler,
+    action: BulkAction,
+    jobs: Vec<Job>,
+    concurrency: usize,
+) -> Vec<JobResult> {
+    futures::stream::iter(jobs)
+        .map(|job| async move {
+            let result = match action {
+                BulkAction::Pause => ags.pause_job(&job.id).await,
+                BulkAction::Resume => ags.resume_job(&job.id).await,
+                BulkAction::Delete => ags.delete_job(&job.id).await,
+                BulkAction::Run => ags.run_job(&job).await,
+            };
+            match result {
+                Ok(()) => JobResult {
+                    id: job.id,
+                    name: job.name,
+                    result: "ok".to_string(),
+                },
+                Err(err) => JobResult::error(job.id, job.name, &err),
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::http;
+
+    #[test]
+    fn it_select() {
+        let now = Utc::now();
+        let jobs: Vec<Job> = serde_json::from_value(json!([
+            {"id": "a", "name": "report-1", "status": "running"},
+            {"id": "b", "name": "report-2", "status": "paused"},
+            {"id": "c", "name": "cleanup", "status": "running"},
+        ]))
+        .unwrap();
+        let ids = |(jobs, missing): (Vec<Job>, Vec<String>)| -> (Vec<String>, Vec<String>) {
+            (jobs.into_iter().map(|j| j.id).collect(), missing)
+        };
+
+        let filter = JobFilter {
+            name: Some("report-*".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            (vec!["a".to_string(), "b".to_string()], vec![]),
+            ids(select(jobs.clone(), &filter, None, now))
+        );
+
+        let stdin = parse_ids("b\nc, y x\n");
+        assert_eq!(HashSet::from(["b", "c", "x", "y"].map(String::from)), stdin);
+        assert_eq!(
+            (
+                vec!["b".to_string()],
+                vec!["x".to_string(), "y".to_string()]
+            ),
+            ids(select(jobs.clone(), &filter, Some(&stdin), now))
+        );
+        assert_eq!(
+            (
+                vec!["b".to_string(), "c".to_string()],
+                vec!["x".to_string(), "y".to_string()]
+            ),
+            ids(select(jobs, &JobFilter::default(), Some(&stdin), now))
+        );
+        assert_eq!(
+            vec![JobResult {
+                id: "x".to_string(),
+                name: String::new(),
+                result: "not found".to_string(),
+            }],
+            not_found(vec!["x".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn it_apply() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/scheduler/job/a/pause")
+            .with_body(json!({"data": null, "error": ""}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/scheduler/job/b/pause")
+            .with_body(json!({"data": null, "error": "`id` not found!"}).to_string())
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&server.url())).unwrap();
+        let jobs: Vec<Job> = serde_json::from_value(json!([
+            {"id": "a", "name": "job-a"},
+            {"id": "b", "name": "job-b"},
+        ]))
+        .unwrap();
+
+        let results = apply(&ags, BulkAction::Pause, jobs, 2).await;
+        assert_eq!(
+            vec![("a", "ok"), ("b", "`id` not found!")],
+            results
+                .iter()
+                .map(|r| (r.id.as_str(), r.result.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+}
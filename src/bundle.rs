@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::models::Job;
+
+/// Version written by `export`, bundles of newer versions are refused by `import`.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum BundleFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl BundleFormat {
+    /// Picks YAML for `.yaml` and `.yml` files, JSON otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => BundleFormat::Yaml,
+            _ => BundleFormat::Json,
+        }
+    }
+}
+
+/// Backup of every job of a scheduler, including their status.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub endpoint: String,
+    pub jobs: Vec<Job>,
+}
+
+impl Bundle {
+    pub fn new(endpoint: &str, jobs: Vec<Job>) -> Self {
+        Bundle {
+            version: BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            endpoint: endpoint.to_string(),
+            jobs,
+        }
+    }
+
+    pub fn to_string(&self, format: BundleFormat) -> anyhow::Result<String> {
+        Ok(match format {
+            BundleFormat::Json => serde_json::to_string_pretty(self)?,
+            BundleFormat::Yaml => serde_yaml::to_string(self)?,
+        })
+    }
+
+    /// Parses a JSON or YAML bundle.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let bundle: Bundle = serde_yaml::from_str(text)?;
+        if bundle.version == 0 || bundle.version > BUNDLE_VERSION {
+            return Err(anyhow::anyhow!(
+                "Unsupported bundle version {}, expected at most {}",
+                bundle.version,
+                BUNDLE_VERSION
+            ));
+        }
+
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_bundle() {
+        let job: Job = serde_json::from_value(json!({
+            "id": "00227fbf671f4ed2",
+            "name": "myJob",
+            "type": "interval",
+            "interval": "60s",
+            "args": {"arg1": "1"},
+            "status": "paused"
+        }))
+        .unwrap();
+        let bundle = Bundle::new("http://127.0.0.1:36370", vec![job]);
+
+        for format in [BundleFormat::Json, BundleFormat::Yaml] {
+            let parsed = Bundle::parse(&bundle.to_string(format).unwrap()).unwrap();
+            assert_eq!(bundle, parsed);
+            assert_eq!("paused", parsed.jobs[0].status);
+        }
+
+        assert!(Bundle::parse(
+            "{\"version\": 2, \"exported_at\": \"\", \"endpoint\": \"\", \"jobs\": []}"
+        )
+        .is_err());
+        assert!(Bundle::parse("{\"jobs\": []}").is_err());
+
+        assert_eq!(
+            BundleFormat::Yaml,
+            BundleFormat::from_path(Path::new("jobs.yml"))
+        );
+        assert_eq!(
+            BundleFormat::Json,
+            BundleFormat::from_path(Path::new("jobs"))
+        );
+    }
+}
@@ -0,0 +1,1947 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::Utc;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use comfy_table::{ContentArrangement, Table};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::api_client::AGScheduler;
+use crate::apply::{self, Action};
+use crate::bulk::{self, BulkAction};
+use crate::bundle::{Bundle, BundleFormat};
+use crate::cron;
+use crate::dashboard;
+use crate::diff;
+use crate::filter::{self, JobFilter, JobSort, RecordFilter};
+use crate::follow;
+use crate::http;
+use crate::interaction::InteractionTrait;
+use crate::models::Job;
+use crate::output::{self, JobResult, OutputFormat};
+use crate::profile::{ConfigFile, Profile};
+use crate::stats;
+use crate::timezone;
+use crate::validate;
+use crate::watch;
+
+/// Command line interface for AGScheduler
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    /// Profile of the config file, or AGSCHEDULERCLI_PROFILE
+    #[arg(short = 'P', long, global = true)]
+    pub profile: Option<String>,
+    /// AGScheduler HTTP endpoint, or AGSCHEDULERCLI_ENDPOINT [default: http://127.0.0.1:36370]
+    #[arg(short, long, global = true)]
+    pub endpoint: Option<String>,
+    /// AGScheduler password
+    /// You can also use the AGSCHEDULERCLI_AUTH environment variable to pass this password more safely
+    #[arg(short, long, global = true, verbatim_doc_comment)]
+    pub password: Option<String>,
+    /// Request timeout in seconds, or AGSCHEDULERCLI_TIMEOUT [default: 6]
+    #[arg(long, global = true, value_name = "SECS")]
+    pub request_timeout: Option<u64>,
+    /// Extra HTTP header as `Name: Value`, can be repeated
+    #[arg(short = 'H', long = "header", global = true, value_name = "HEADER", value_parser = parse_header)]
+    pub headers: Vec<(String, String)>,
+    /// Use HTTP/2 without negotiation, for servers behind an h2c proxy
+    #[arg(long, global = true)]
+    pub http2: bool,
+    /// Accept invalid TLS certificates
+    #[arg(long, global = true)]
+    pub insecure: bool,
+    /// Extra PEM root certificate to trust
+    #[arg(long, global = true, value_name = "FILE")]
+    pub ca_cert: Option<PathBuf>,
+    /// Print the requests that would change something, without sending them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    /// Output format, or AGSCHEDULERCLI_OUTPUT [default: table]
+    #[arg(short, long, global = true, value_enum)]
+    pub output: Option<OutputFormat>,
+    /// Run a single operation instead of opening the interactive menu
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+fn parse_header(header: &str) -> Result<(String, String), String> {
+    match header.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("expected `Name: Value`, got `{header}`")),
+    }
+}
+
+impl Cli {
+    /// Picks the profile of `--profile`, `AGSCHEDULERCLI_PROFILE` or the current one of the config file.
+    pub fn profile(&self, file: &ConfigFile) -> anyhow::Result<Profile> {
+        let name = self
+            .profile
+            .clone()
+            .or_else(|| env::var("AGSCHEDULERCLI_PROFILE").ok());
+
+        file.profile(name.as_deref())
+    }
+
+    /// Builds the connection config, flags take precedence over environment variables over the profile.
+    pub fn config(&self, profile: &Profile) -> anyhow::Result<http::Config> {
+        self._config(profile, |name| env::var(name).ok())
+    }
+
+    fn _config(
+        &self,
+        profile: &Profile,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<http::Config> {
+        let mut config = http::Config::default();
+
+        if let Some(endpoint) = self
+            .endpoint
+            .clone()
+            .or_else(|| env("AGSCHEDULERCLI_ENDPOINT"))
+            .or_else(|| profile.endpoint.clone())
+        {
+            config.endpoint = endpoint;
+        }
+
+        let auth = match self.password.clone().or_else(|| env("AGSCHEDULERCLI_AUTH")) {
+            Some(password) => Some(password),
+            None => profile.password()?,
+        };
+        config = config.with_password(&auth.unwrap_or_default());
+
+        let timeout = match (self.request_timeout, env("AGSCHEDULERCLI_TIMEOUT")) {
+            (Some(timeout), _) => Some(timeout),
+            (None, Some(timeout)) => Some(
+                timeout
+                    .parse()
+                    .map_err(|err| anyhow::anyhow!("Invalid `AGSCHEDULERCLI_TIMEOUT`: {err}"))?,
+            ),
+            (None, None) => profile.timeout,
+        };
+        if let Some(timeout) = timeout {
+            config = config.with_timeout(Duration::from_secs(timeout));
+        }
+
+        config = config
+            .with_http2(self.http2 || profile.http2.unwrap_or_default())
+            .with_insecure(self.insecure || profile.insecure.unwrap_or_default())
+            .with_ca_cert(self.ca_cert.clone().or_else(|| profile.ca_cert.clone()))
+            .with_dry_run(self.dry_run);
+        for (name, value) in &profile.headers {
+            config = config.with_header(name, value);
+        }
+        for (name, value) in &self.headers {
+            config = config.with_header(name, value);
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the output format of `--output`, `AGSCHEDULERCLI_OUTPUT` or the profile.
+    pub fn output(&self, profile: &Profile) -> anyhow::Result<OutputFormat> {
+        self._output(profile, |name| env::var(name).ok())
+    }
+
+    fn _output(
+        &self,
+        profile: &Profile,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<OutputFormat> {
+        if let Some(output) = self.output {
+            return Ok(output);
+        }
+        if let Some(output) = env("AGSCHEDULERCLI_OUTPUT") {
+            return OutputFormat::from_str(&output, true)
+                .map_err(|err| anyhow::anyhow!("Invalid `AGSCHEDULERCLI_OUTPUT`: {err}"));
+        }
+
+        Ok(profile.output.unwrap_or_default())
+    }
+
+    /// Returns a profile holding only the connection flags given on the command line.
+    pub fn to_profile(&self) -> Profile {
+        Profile {
+            endpoint: self.endpoint.clone(),
+            password: self.password.clone(),
+            timeout: self.request_timeout,
+            http2: self.http2.then_some(true),
+            insecure: self.insecure.then_some(true),
+            ca_cert: self.ca_cert.clone(),
+            headers: self.headers.iter().cloned().collect(),
+            output: self.output,
+            ..Default::default()
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Add, get, list, update, delete, pause, resume, run or schedule jobs
+    #[command(subcommand)]
+    Job(JobCommand),
+    /// Start or stop the scheduler
+    #[command(subcommand)]
+    Scheduler(SchedulerCommand),
+    /// List or delete records
+    #[command(subcommand)]
+    Records(RecordsCommand),
+    /// Show run statistics per job, aggregated from every record
+    Stats {
+        /// Only the records of this job
+        #[arg(long, default_value = "")]
+        job_id: String,
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+        /// Number of pages fetched at the same time
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..=32))]
+        concurrency: u64,
+        #[command(flatten)]
+        filter: RecordFilter,
+    },
+    /// Show scheduler info
+    Info,
+    /// List registered funcs
+    Funcs,
+    /// List broker queues
+    Queues {
+        #[command(flatten)]
+        watch: WatchArgs,
+    },
+    /// Show cluster state
+    #[command(subcommand)]
+    Cluster(ClusterCommand),
+    /// Check a cron expression and show its next fire times
+    Cron {
+        expr: String,
+        /// Timezone of the fire times [default: local timezone]
+        #[arg(long)]
+        timezone: Option<String>,
+        /// Number of fire times
+        #[arg(short = 'n', long, default_value_t = 5)]
+        count: usize,
+    },
+    /// List IANA timezones with their current UTC offsets
+    Timezones {
+        /// Only list the timezones whose name contains this text
+        filter: Option<String>,
+    },
+    /// Open a full-screen dashboard of jobs, records, queues and cluster nodes
+    Dashboard {
+        /// Refresh interval in seconds
+        #[arg(short, long, value_name = "SECS", default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Manage the profiles of the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Create or update the jobs of a YAML or JSON spec file, matched by name
+    Apply {
+        /// Spec file, `-` reads stdin, only the plan is printed with `--dry-run`
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    /// Write every job to a versioned JSON or YAML bundle
+    Export {
+        /// Bundle file, stdout if not set
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+        /// Bundle format [default: from the file extension, or json]
+        #[arg(long, value_enum)]
+        format: Option<BundleFormat>,
+    },
+    /// Recreate the jobs of a bundle
+    Import {
+        /// Bundle file, `-` reads stdin
+        #[arg(short, long)]
+        file: PathBuf,
+        /// Let the scheduler generate new IDs instead of keeping the exported ones
+        #[arg(long)]
+        new_ids: bool,
+        /// Pause the jobs that were paused when exported
+        #[arg(long)]
+        restore_paused: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum JobCommand {
+    /// Add a job
+    Add(JobArgs),
+    /// Get a job
+    Get { id: String },
+    /// List jobs, optionally filtered and sorted
+    List {
+        #[command(flatten)]
+        filter: JobFilter,
+        /// Sort the jobs, server order if not set
+        #[arg(long, value_enum)]
+        sort: Option<JobSort>,
+        #[command(flatten)]
+        watch: WatchArgs,
+    },
+    /// Update the given fields of a job, keeping the others
+    Update {
+        id: String,
+        #[command(flatten)]
+        job: JobArgs,
+        /// Set a field by its name in the job body, e.g. `timeout=30m`, can be repeated
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_set)]
+        set: Vec<(String, String)>,
+        /// Skip the confirmation prompt after the diff, only asked in a terminal
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Delete a job, or all jobs with `--all`
+    Delete {
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+        /// Delete all jobs
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Pause a job
+    Pause { id: String },
+    /// Resume a job
+    Resume { id: String },
+    /// Run a job once immediately
+    Run { id: String },
+    /// Schedule a job once immediately
+    Schedule { id: String },
+    /// Pause, resume, delete or run every job matching the selector, after one confirmation
+    Bulk {
+        #[arg(value_enum)]
+        action: BulkAction,
+        #[command(flatten)]
+        filter: JobFilter,
+        /// Only the jobs whose ID is listed on stdin, separated by whitespace or commas
+        #[arg(long)]
+        stdin: bool,
+        /// Number of jobs processed at the same time
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..=32))]
+        concurrency: u64,
+        /// Skip the confirmation prompt, needed when stdin is not a terminal
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Args, Debug, Default)]
+pub struct JobArgs {
+    /// Job name [add default: myJob]
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Job type [add default: interval]
+    #[arg(long = "type", value_parser = ["datetime", "interval", "cron"])]
+    pub job_type: Option<String>,
+    /// Start time of a datetime job, e.g. `2024-04-16 15:23:51`
+    #[arg(long)]
+    pub start_at: Option<String>,
+    /// Interval of an interval job [add default: 60s]
+    #[arg(long)]
+    pub interval: Option<String>,
+    /// Cron expression of a cron job [add default: */1 * * * *]
+    #[arg(long)]
+    pub cron_expr: Option<String>,
+    /// Timezone [add default: local timezone]
+    #[arg(long)]
+    pub timezone: Option<String>,
+    /// Func name, required when adding
+    #[arg(long)]
+    pub func_name: Option<String>,
+    /// Args as a JSON object [add default: {}]
+    #[arg(long)]
+    pub args: Option<String>,
+    /// Timeout [add default: 1h]
+    #[arg(long)]
+    pub timeout: Option<String>,
+    /// Queues as a JSON array [add default: []]
+    #[arg(long)]
+    pub queues: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct WatchArgs {
+    /// Redraw every SECS seconds until Ctrl-C, highlighting changed rows
+    #[arg(short, long, value_name = "SECS", num_args = 0..=1, default_missing_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+    pub watch: Option<u64>,
+}
+
+impl WatchArgs {
+    /// Returns the refresh interval if watching, which needs the table output.
+    pub fn interval(&self, format: OutputFormat) -> anyhow::Result<Option<Duration>> {
+        match self.watch {
+            Some(_) if format != OutputFormat::Table => {
+                Err(anyhow::anyhow!("`--watch` needs the table output"))
+            }
+            Some(secs) => Ok(Some(Duration::from_secs(secs))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SchedulerCommand {
+    /// Start the scheduler
+    Start,
+    /// Stop the scheduler
+    Stop,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand, Debug)]
+pub enum RecordsCommand {
+    /// List one page of records, or every page with `--all`
+    List {
+        /// Only list the records of this job
+        #[arg(long, visible_alias = "job", default_value = "")]
+        job_id: String,
+        #[arg(long, default_value_t = 1, conflicts_with = "all")]
+        page: u64,
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+        /// Walk every page, printing records as they are fetched
+        #[arg(long, conflicts_with = "watch")]
+        all: bool,
+        /// Number of pages fetched at the same time with `--all`
+        #[arg(long, default_value_t = 4, requires = "all", value_parser = clap::value_parser!(u64).range(1..=32))]
+        concurrency: u64,
+        /// Stop after this many records with `--all`
+        #[arg(long, requires = "all")]
+        limit: Option<usize>,
+        #[command(flatten)]
+        filter: RecordFilter,
+        /// Poll every SECS seconds until Ctrl-C, printing new records and the ones that finished
+        #[arg(short, long, value_name = "SECS", num_args = 0..=1, default_missing_value = "2", value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = ["all", "watch", "page"])]
+        follow: Option<u64>,
+        #[command(flatten)]
+        watch: WatchArgs,
+    },
+    /// Delete the records of a job, or all records with `--all`
+    Delete {
+        #[arg(long, required_unless_present = "all")]
+        job_id: Option<String>,
+        /// Delete all records
+        #[arg(long, conflicts_with = "job_id")]
+        all: bool,
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ClusterCommand {
+    /// List cluster nodes
+    Nodes {
+        #[command(flatten)]
+        watch: WatchArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// List profiles
+    List,
+    /// Save the connection flags given on the command line as a profile
+    Add {
+        name: String,
+        /// Read the password from this environment variable when the profile is used
+        #[arg(long, value_name = "VAR")]
+        password_env: Option<String>,
+        /// Also make it the current profile
+        #[arg(long = "use")]
+        use_profile: bool,
+    },
+    /// Remove a profile
+    Remove { name: String },
+    /// Make a profile the current one
+    Use { name: String },
+}
+
+#[derive(Serialize)]
+struct ProfileRow<'a> {
+    name: &'a str,
+    current: bool,
+    #[serde(flatten)]
+    profile: Profile,
+}
+
+/// Runs a `config` command against the config file at `path`, without connecting to the scheduler.
+pub fn run_config(
+    cli: &Cli,
+    command: &ConfigCommand,
+    path: &Path,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let mut file = ConfigFile::load(path)?;
+    match command {
+        ConfigCommand::List => {
+            let rows: Vec<ProfileRow> = file
+                .profiles
+                .iter()
+                .map(|(name, profile)| ProfileRow {
+                    name,
+                    current: file.current.as_ref() == Some(name),
+                    profile: Profile {
+                        password: profile.password.as_ref().map(|_| "***".to_string()),
+                        ..profile.clone()
+                    },
+                })
+                .collect();
+            if format != OutputFormat::Table {
+                return output::show_list(&rows, format);
+            }
+
+            let mut table = Table::new();
+            table
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec!["Current", "Name", "Endpoint", "Auth", "Output"]);
+            for row in &rows {
+                let auth = match (&row.profile.password_env, &row.profile.password) {
+                    (Some(name), _) => format!("${name}"),
+                    (None, Some(_)) => "password".to_string(),
+                    (None, None) => String::new(),
+                };
+                table.add_row(vec![
+                    if row.current { "*" } else { "" }.to_string(),
+                    row.name.to_string(),
+                    row.profile.endpoint.clone().unwrap_or_default(),
+                    auth,
+                    row.profile
+                        .output
+                        .and_then(|o| o.to_possible_value())
+                        .map(|v| v.get_name().to_string())
+                        .unwrap_or_default(),
+                ]);
+            }
+            println!("{table}");
+            println!("Total {}  Config {}", rows.len(), path.display());
+
+            return Ok(());
+        }
+        ConfigCommand::Add {
+            name,
+            password_env,
+            use_profile,
+        } => {
+            let mut profile = cli.to_profile();
+            if password_env.is_some() {
+                profile.password = None;
+                profile.password_env = password_env.clone();
+            }
+            file.profiles.insert(name.to_string(), profile);
+            if *use_profile || file.current.is_none() {
+                file.use_profile(name)?;
+            }
+        }
+        ConfigCommand::Remove { name } => file.remove(name)?,
+        ConfigCommand::Use { name } => file.use_profile(name)?,
+    }
+    file.save(path)?;
+    output::show_ok(format);
+
+    Ok(())
+}
+
+/// Fields of the job body that `--set` can change.
+const SET_FIELDS: [&str; 10] = [
+    "name",
+    "type",
+    "start_at",
+    "interval",
+    "cron_expr",
+    "timezone",
+    "func_name",
+    "args",
+    "timeout",
+    "queues",
+];
+
+fn parse_set(text: &str) -> Result<(String, String), String> {
+    let (key, value) = text
+        .split_once('=')
+        .ok_or_else(|| format!("expected `key=value`, got `{text}`"))?;
+    if !SET_FIELDS.contains(&key) {
+        return Err(format!(
+            "unknown field `{key}`, expected one of: {}",
+            SET_FIELDS.join(", ")
+        ));
+    }
+
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Sets a field parsed by `parse_set`, leaving the others as they are.
+fn set_field(job: &mut Job, key: &str, value: &str) -> anyhow::Result<()> {
+    let field = match key {
+        "name" => &mut job.name,
+        "type" => &mut job.job_type,
+        "start_at" => &mut job.start_at,
+        "interval" => &mut job.interval,
+        "cron_expr" => &mut job.cron_expr,
+        "timezone" => &mut job.timezone,
+        "func_name" => &mut job.func_name,
+        "timeout" => &mut job.timeout,
+        "args" => {
+            job.args = parse_json("set args", value)?;
+            return Ok(());
+        }
+        "queues" => {
+            job.queues = parse_json("set queues", value)?;
+            return Ok(());
+        }
+        _ => return Err(anyhow::anyhow!("Unknown field `{key}`")),
+    };
+    *field = value.to_string();
+
+    Ok(())
+}
+
+fn parse_json<T: DeserializeOwned>(flag: &str, text: &str) -> anyhow::Result<T> {
+    serde_json::from_str(text).map_err(|err| anyhow::anyhow!("Invalid `--{flag}`: {err}"))
+}
+
+impl JobArgs {
+    /// Builds a new job, filling unset fields with the interactive defaults.
+    pub fn to_job(&self) -> anyhow::Result<Job> {
+        let func_name = self
+            .func_name
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("`--func-name` is required"))?;
+        let _type = self.job_type.clone().unwrap_or("interval".to_string());
+
+        let mut start_at = String::new();
+        let mut interval = String::new();
+        let mut cron_expr = String::new();
+        match _type.as_str() {
+            "datetime" => {
+                start_at = self
+                    .start_at
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("`--start-at` is required"))?;
+            }
+            "interval" => {
+                interval = self.interval.clone().unwrap_or("60s".to_string());
+            }
+            "cron" => {
+                cron_expr = self.cron_expr.clone().unwrap_or("*/1 * * * *".to_string());
+            }
+            _ => {}
+        }
+
+        let timezone = match &self.timezone {
+            Some(tz) => tz.to_string(),
+            None => iana_time_zone::get_timezone()?,
+        };
+
+        Ok(Job {
+            name: self.name.clone().unwrap_or("myJob".to_string()),
+            job_type: _type,
+            start_at,
+            interval,
+            cron_expr,
+            timezone,
+            func_name,
+            args: parse_json("args", self.args.as_deref().unwrap_or("{}"))?,
+            timeout: self.timeout.clone().unwrap_or("1h".to_string()),
+            queues: parse_json("queues", self.queues.as_deref().unwrap_or("[]"))?,
+            ..Default::default()
+        })
+    }
+
+    /// Overwrites only the fields that were passed on the command line.
+    pub fn apply_to(&self, job: &mut Job) -> anyhow::Result<()> {
+        for (field, value) in [
+            (&mut job.name, &self.name),
+            (&mut job.job_type, &self.job_type),
+            (&mut job.start_at, &self.start_at),
+            (&mut job.interval, &self.interval),
+            (&mut job.cron_expr, &self.cron_expr),
+            (&mut job.timezone, &self.timezone),
+            (&mut job.func_name, &self.func_name),
+            (&mut job.timeout, &self.timeout),
+        ] {
+            if let Some(v) = value {
+                *field = v.to_string();
+            }
+        }
+        if let Some(args) = &self.args {
+            job.args = parse_json("args", args)?;
+        }
+        if let Some(queues) = &self.queues {
+            job.queues = parse_json("queues", queues)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a whole file, or stdin for `-`.
+fn read_input(path: &Path) -> anyhow::Result<String> {
+    if path.as_os_str() == "-" {
+        return Ok(io::read_to_string(io::stdin())?);
+    }
+
+    fs::read_to_string(path).map_err(|err| anyhow::anyhow!("Read `{}`: {err}", path.display()))
+}
+
+/// Recreates the jobs of a bundle one by one, failures are reported with the exported ID.
+async fn import_jobs(
+    ags: &AGScheduler,
+    jobs: Vec<Job>,
+    new_ids: bool,
+    restore_paused: bool,
+) -> Vec<JobResult> {
+    let mut results = vec![];
+    for mut job in jobs {
+        let exported_id = job.id.to_string();
+        if new_ids {
+            job.id = String::new();
+        }
+        let result = async {
+            let added = ags.add_job(&job).await?;
+            if restore_paused && job.status == "paused" {
+                ags.pause_job(&added.id).await?;
+            }
+
+            anyhow::Ok(added.id)
+        }
+        .await;
+        results.push(match result {
+            Ok(id) => JobResult {
+                id,
+                name: job.name,
+                result: "ok".to_string(),
+            },
+            Err(err) => JobResult::error(exported_id, job.name, &err),
+        });
+    }
+
+    results
+}
+
+/// Prints every record matching `filter`, page by page, stopping after `limit` records.
+pub async fn list_all_records(
+    ags: &AGScheduler,
+    job_id: &str,
+    filter: &RecordFilter,
+    page_size: u64,
+    concurrency: usize,
+    limit: Option<usize>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let now = Utc::now();
+    let mut stream = output::RecordStream::new(format);
+    let total = ags
+        .walk_records(job_id, page_size, concurrency, |records| {
+            let mut records = filter.apply(records, now);
+            let remaining = limit.map(|l| l.saturating_sub(stream.count()));
+            if let Some(remaining) = remaining {
+                records.truncate(remaining);
+            }
+            stream.push(records)?;
+
+            Ok(limit.is_none_or(|l| stream.count() < l))
+        })
+        .await?;
+
+    stream.finish(total)
+}
+
+/// Runs a single non-interactive command, only prompting to confirm deletions and updates without `--yes`.
+pub async fn run(
+    ags: &AGScheduler,
+    command: Command,
+    format: OutputFormat,
+    interaction: &dyn InteractionTrait,
+) -> anyhow::Result<()> {
+    match run_command(ags, command, format, interaction).await {
+        Err(err) => match err.downcast::<http::DryRun>() {
+            Ok(request) => output::show_dry_run(&request, format),
+            Err(err) => Err(err),
+        },
+        result => result,
+    }
+}
+
+async fn run_command(
+    ags: &AGScheduler,
+    command: Command,
+    format: OutputFormat,
+    interaction: &dyn InteractionTrait,
+) -> anyhow::Result<()> {
+    match command {
+        Command::Job(job_command) => match job_command {
+            JobCommand::Add(job) => {
+                let job = job.to_job()?;
+                validate::job(&job)?;
+                output::show_warnings(&validate::warnings(&job, Utc::now()));
+                let job = ags.add_job(&job).await?;
+                output::show_object(&job, format)
+            }
+            JobCommand::Get { id } => output::show_object(&ags.get_job(&id).await?, format),
+            JobCommand::List {
+                filter,
+                sort,
+                watch: watch_args,
+            } => {
+                if let Some(interval) = watch_args.interval(format)? {
+                    return watch::watch(interval, "job list", || async {
+                        let jobs = ags.list_jobs().await?;
+                        let total = jobs.len();
+                        let jobs = filter::select_jobs(jobs, &filter, sort, Utc::now());
+                        Ok((
+                            output::jobs_table(&jobs),
+                            output::jobs_footer(jobs.len(), total),
+                        ))
+                    })
+                    .await;
+                }
+
+                let jobs = ags.list_jobs().await?;
+                let total = jobs.len();
+                let jobs = filter::select_jobs(jobs, &filter, sort, Utc::now());
+                output::show_jobs(&jobs, total, format)
+            }
+            JobCommand::Update {
+                id,
+                job: job_args,
+                set,
+                yes,
+            } => {
+                let current = ags.get_job(&id).await?;
+                let mut job = current.clone();
+                job_args.apply_to(&mut job)?;
+                for (key, value) in &set {
+                    set_field(&mut job, key, value)?;
+                }
+                validate::job(&job)?;
+                output::show_warnings(&validate::warnings(&job, Utc::now()));
+
+                let changes = diff::jobs(&current, &job);
+                eprintln!("{}", output::format_job_diff(&id, &changes));
+                if changes.is_empty() {
+                    return Ok(());
+                }
+                // Scripts without a terminal update without asking, as before the confirmation.
+                let confirm = !yes && !ags.client.config.dry_run && interaction.is_terminal();
+                if confirm && !interaction.confirm_common(&format!("Update job `{}`?", job.name)) {
+                    return Ok(());
+                }
+                let job = ags.update_job(&job).await?;
+                output::show_object(&job, format)
+            }
+            JobCommand::Delete { id, all, yes } => {
+                if !yes && !interaction.confirm_delete() {
+                    return Ok(());
+                }
+                match id {
+                    Some(id) if !all => ags.delete_job(&id).await?,
+                    _ => ags.delete_all_jobs().await?,
+                }
+                output::show_ok(format);
+
+                Ok(())
+            }
+            JobCommand::Pause { id } => {
+                ags.pause_job(&id).await?;
+                output::show_ok(format);
+
+                Ok(())
+            }
+            JobCommand::Resume { id } => {
+                ags.resume_job(&id).await?;
+                output::show_ok(format);
+
+                Ok(())
+            }
+            JobCommand::Run { id } => {
+                ags.run_job(&ags.get_job(&id).await?).await?;
+                output::show_ok(format);
+
+                Ok(())
+            }
+            JobCommand::Schedule { id } => {
+                ags.schedule_job(&ags.get_job(&id).await?).await?;
+                output::show_ok(format);
+
+                Ok(())
+            }
+            JobCommand::Bulk {
+                action,
+                filter,
+                stdin,
+                concurrency,
+                yes,
+            } => {
+                if filter.is_empty() && !stdin {
+                    return Err(anyhow::anyhow!(
+                        "Select the jobs with at least one filter or `--stdin`"
+                    ));
+                }
+                let ids = match stdin {
+                    true => Some(bulk::parse_ids(&read_input(Path::new("-"))?)),
+                    false => None,
+                };
+
+                let (jobs, missing) =
+                    bulk::select(ags.list_jobs().await?, &filter, ids.as_ref(), Utc::now());
+                if jobs.is_empty() && missing.is_empty() {
+                    eprintln!("No jobs matched");
+                    return Ok(());
+                }
+                if !jobs.is_empty() {
+                    output::preview_jobs(&jobs);
+                    let prompt = format!("{} {} jobs?", action.name(), jobs.len());
+                    if !yes && !interaction.confirm_common(&prompt) {
+                        return Ok(());
+                    }
+                }
+
+                let mut results = bulk::apply(ags, action, jobs, concurrency as usize).await;
+                results.extend(bulk::not_found(missing));
+                output::show_job_results(&results, format)?;
+                output::check_results(&results)
+            }
+        },
+        Command::Scheduler(scheduler_command) => {
+            match scheduler_command {
+                SchedulerCommand::Start => ags.start().await?,
+                SchedulerCommand::Stop => ags.stop().await?,
+            }
+            output::show_ok(format);
+
+            Ok(())
+        }
+        Command::Records(records_command) => match records_command {
+            RecordsCommand::List {
+                job_id,
+                page,
+                page_size,
+                all,
+                concurrency,
+                limit,
+                filter,
+                follow: follow_secs,
+                watch: watch_args,
+            } => {
+                if let Some(secs) = follow_secs {
+                    return follow::follow(
+                        ags,
+                        &job_id,
+                        &filter,
+                        page_size,
+                        Duration::from_secs(secs),
+                        format,
+                    )
+                    .await;
+                }
+                if all {
+                    return list_all_records(
+                        ags,
+                        &job_id,
+                        &filter,
+                        page_size,
+                        concurrency as usize,
+                        limit,
+                        format,
+                    )
+                    .await;
+                }
+
+                if let Some(interval) = watch_args.interval(format)? {
+                    return watch::watch(interval, "records list", || async {
+                        let mut records = ags.list_records(&job_id, page, page_size).await?;
+                        records.res = filter.apply(records.res, Utc::now());
+                        Ok((
+                            output::records_table(&records),
+                            output::records_footer(&records),
+                        ))
+                    })
+                    .await;
+                }
+
+                let mut records = ags.list_records(&job_id, page, page_size).await?;
+                records.res = filter.apply(records.res, Utc::now());
+                output::show_records(&records, format)
+            }
+            RecordsCommand::Delete { job_id, all, yes } => {
+                if !yes && !interaction.confirm_delete() {
+                    return Ok(());
+                }
+                match job_id {
+                    Some(job_id) if !all => ags.delete_records(&job_id).await?,
+                    _ => ags.delete_records("").await?,
+                }
+                output::show_ok(format);
+
+                Ok(())
+            }
+        },
+        Command::Info => output::show_object(&ags.get_info().await?, format),
+        Command::Funcs => output::show_funcs(&ags.list_funcs().await?, format),
+        Command::Queues { watch: watch_args } => {
+            if let Some(interval) = watch_args.interval(format)? {
+                return watch::watch(interval, "queues", || async {
+                    let queues = ags.list_queues().await?;
+                    Ok((
+                        output::queues_table(&queues),
+                        format!("Total {}", queues.len()),
+                    ))
+                })
+                .await;
+            }
+
+            output::show_queues(&ags.list_queues().await?, format)
+        }
+        Command::Cluster(ClusterCommand::Nodes { watch: watch_args }) => {
+            if let Some(interval) = watch_args.interval(format)? {
+                return watch::watch(interval, "cluster nodes", || async {
+                    let nodes = ags.list_cluster_nodes().await?;
+                    Ok((
+                        output::cluster_nodes_table(&nodes),
+                        format!("Total {}", nodes.len()),
+                    ))
+                })
+                .await;
+            }
+
+            output::show_cluster_nodes(&ags.list_cluster_nodes().await?, format)
+        }
+        Command::Apply { file } => {
+            let specs = apply::parse_specs(&read_input(&file)?)
+                .map_err(|err| anyhow::anyhow!("Invalid `{}`: {err}", file.display()))?;
+            let changes = apply::plan(&specs, &ags.list_jobs().await?)?;
+            output::show_plan(&changes, format)?;
+            if ags.client.config.dry_run {
+                return Ok(());
+            }
+
+            for change in &changes {
+                match change.action {
+                    Action::Create => ags.add_job(&change.job).await?,
+                    Action::Update => ags.update_job(&change.job).await?,
+                    Action::Unchanged => continue,
+                };
+            }
+            output::show_ok(format);
+
+            Ok(())
+        }
+        Command::Export {
+            file,
+            format: bundle_format,
+        } => {
+            let bundle = Bundle::new(&ags.client.config.endpoint, ags.list_jobs().await?);
+            match file {
+                Some(file) => {
+                    let bundle_format = bundle_format.unwrap_or(BundleFormat::from_path(&file));
+                    fs::write(&file, bundle.to_string(bundle_format)?)?;
+                    output::show_ok(format);
+                }
+                None => println!("{}", bundle.to_string(bundle_format.unwrap_or_default())?),
+            }
+
+            Ok(())
+        }
+        Command::Import {
+            file,
+            new_ids,
+            restore_paused,
+        } => {
+            let bundle = Bundle::parse(&read_input(&file)?)
+                .map_err(|err| anyhow::anyhow!("Invalid `{}`: {err}", file.display()))?;
+
+            let results = import_jobs(ags, bundle.jobs, new_ids, restore_paused).await;
+            output::show_job_results(&results, format)?;
+            output::check_results(&results)
+        }
+        Command::Cron {
+            expr,
+            timezone,
+            count,
+        } => {
+            let timezone = match timezone {
+                Some(tz) => tz,
+                None => iana_time_zone::get_timezone()?,
+            };
+            output::show_fire_times(&cron::next_fire_times(&expr, &timezone, count)?, format)
+        }
+        Command::Stats {
+            job_id,
+            page_size,
+            concurrency,
+            filter,
+        } => {
+            let now = Utc::now();
+            let mut records = vec![];
+            ags.walk_records(&job_id, page_size, concurrency as usize, |page| {
+                records.extend(filter.apply(page, now));
+                Ok(true)
+            })
+            .await?;
+
+            output::show_stats(&stats::aggregate(&records), format)
+        }
+        Command::Timezones { filter } => {
+            output::show_timezones(&timezone::zones(filter.as_deref(), Utc::now()), format)
+        }
+        Command::Dashboard { interval } => dashboard::run(ags, Duration::from_secs(interval)).await,
+        Command::Config(_) => Err(anyhow::anyhow!(
+            "`config` commands do not connect to the scheduler, use `run_config`"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::interaction::MockInteractionTrait;
+
+    fn parse(args: &[&str]) -> Command {
+        let mut argv = vec!["agscheduler-cli"];
+        argv.extend_from_slice(args);
+        Cli::try_parse_from(argv).unwrap().command.unwrap()
+    }
+
+    #[test]
+    fn it_parse_no_command() {
+        let cli = Cli::try_parse_from(["agscheduler-cli", "-e", "http://127.0.0.1:1"]).unwrap();
+
+        assert_eq!(Some("http://127.0.0.1:1".to_string()), cli.endpoint);
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn it_cli_config() {
+        let cli = Cli::try_parse_from([
+            "agscheduler-cli",
+            "-p",
+            "123",
+            "--request-timeout",
+            "30",
+            "-H",
+            "X-Request-Source: ci",
+            "--http2",
+        ])
+        .unwrap();
+
+        let config = cli._config(&Profile::default(), |_| None).unwrap();
+        assert_eq!(
+            http::Config::new("").with_password("123").password_sha2,
+            config.password_sha2
+        );
+        assert_eq!(Duration::from_secs(30), config.timeout);
+        assert_eq!(
+            vec![("X-Request-Source".to_string(), "ci".to_string())],
+            config.headers
+        );
+        assert!(config.http2);
+
+        assert!(Cli::try_parse_from(["agscheduler-cli", "-H", "no-colon"]).is_err());
+    }
+
+    #[test]
+    fn it_cli_config_precedence() {
+        let profile = Profile {
+            endpoint: Some("http://profile:36370".to_string()),
+            password: Some("profile".to_string()),
+            timeout: Some(10),
+            insecure: Some(true),
+            headers: [("X-Profile".to_string(), "1".to_string())].into(),
+            output: Some(OutputFormat::Yaml),
+            ..Default::default()
+        };
+        let env = |name: &str| match name {
+            "AGSCHEDULERCLI_ENDPOINT" => Some("http://env:36370".to_string()),
+            "AGSCHEDULERCLI_TIMEOUT" => Some("20".to_string()),
+            "AGSCHEDULERCLI_OUTPUT" => Some("csv".to_string()),
+            _ => None,
+        };
+
+        let cli = Cli::try_parse_from(["agscheduler-cli"]).unwrap();
+        let config = cli._config(&profile, |_| None).unwrap();
+        assert_eq!("http://profile:36370", config.endpoint);
+        assert_eq!(
+            http::Config::new("").with_password("profile").password_sha2,
+            config.password_sha2
+        );
+        assert_eq!(Duration::from_secs(10), config.timeout);
+        assert!(config.insecure);
+        assert_eq!(OutputFormat::Yaml, cli._output(&profile, |_| None).unwrap());
+
+        let config = cli._config(&profile, env).unwrap();
+        assert_eq!("http://env:36370", config.endpoint);
+        assert_eq!(Duration::from_secs(20), config.timeout);
+        assert_eq!(OutputFormat::Csv, cli._output(&profile, env).unwrap());
+
+        let cli = Cli::try_parse_from([
+            "agscheduler-cli",
+            "-e",
+            "http://flag:36370",
+            "--request-timeout",
+            "30",
+            "-o",
+            "json",
+            "-H",
+            "X-Flag: 1",
+        ])
+        .unwrap();
+        let config = cli._config(&profile, env).unwrap();
+        assert_eq!("http://flag:36370", config.endpoint);
+        assert_eq!(Duration::from_secs(30), config.timeout);
+        assert_eq!(2, config.headers.len());
+        assert_eq!(OutputFormat::Json, cli._output(&profile, env).unwrap());
+
+        assert!(cli
+            ._config(&profile, |_| Some("x".to_string()))
+            .is_ok_and(|c| c.endpoint == "http://flag:36370"));
+        let cli = Cli::try_parse_from(["agscheduler-cli"]).unwrap();
+        assert!(cli._config(&profile, |_| Some("x".to_string())).is_err());
+        assert!(cli._output(&profile, |_| Some("x".to_string())).is_err());
+    }
+
+    #[test]
+    fn it_run_config() {
+        let dir =
+            env::temp_dir().join(format!("agscheduler-cli-run-config-{}", std::process::id()));
+        let path = dir.join("config.toml");
+
+        let cli = Cli::try_parse_from([
+            "agscheduler-cli",
+            "-e",
+            "http://prod:36370",
+            "-p",
+            "123",
+            "config",
+            "add",
+            "prod",
+            "--password-env",
+            "PROD_PASSWORD",
+        ])
+        .unwrap();
+        let Some(Command::Config(command)) = &cli.command else {
+            panic!("expected a config command");
+        };
+        run_config(&cli, command, &path, OutputFormat::Table).unwrap();
+
+        let file = ConfigFile::load(&path).unwrap();
+        assert_eq!(Some("prod".to_string()), file.current);
+        let prod = &file.profiles["prod"];
+        assert_eq!(Some("http://prod:36370".to_string()), prod.endpoint);
+        assert_eq!(None, prod.password);
+        assert_eq!(Some("PROD_PASSWORD".to_string()), prod.password_env);
+
+        for args in [
+            vec!["config", "add", "dev"],
+            vec!["config", "use", "dev"],
+            vec!["config", "list"],
+            vec!["config", "remove", "prod"],
+        ] {
+            let cli = Cli::try_parse_from([vec!["agscheduler-cli"], args].concat()).unwrap();
+            let Some(Command::Config(command)) = &cli.command else {
+                panic!("expected a config command");
+            };
+            run_config(&cli, command, &path, OutputFormat::Json).unwrap();
+        }
+
+        let file = ConfigFile::load(&path).unwrap();
+        assert_eq!(Some("dev".to_string()), file.current);
+        assert_eq!(vec!["dev"], file.profiles.keys().collect::<Vec<_>>());
+        assert!(matches!(
+            parse(&["config", "use", "prod"]),
+            Command::Config(ConfigCommand::Use { .. })
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn it_parse_records_filter() {
+        let Command::Records(RecordsCommand::List { filter, all, .. }) = parse(&[
+            "records", "list", "--all", "--status", "timeout", "--since", "-12h", "--result",
+            "deadline",
+        ]) else {
+            panic!("expected records list");
+        };
+        assert!(all);
+        assert_eq!(Some("timeout".to_string()), filter.status);
+        assert_eq!(
+            Some(filter::Moment::Ago(Duration::from_secs(12 * 3600))),
+            filter.since
+        );
+        assert!(filter.result.unwrap().is_match("context deadline exceeded"));
+
+        assert!(
+            Cli::try_parse_from(["agscheduler-cli", "records", "list", "--status", "done"])
+                .is_err()
+        );
+        for status in ["completed", "success"] {
+            let Command::Records(RecordsCommand::List { filter, .. }) =
+                parse(&["records", "list", "--status", status])
+            else {
+                panic!("expected records list");
+            };
+            assert_eq!(Some("completed".to_string()), filter.status);
+        }
+    }
+
+    #[test]
+    fn it_parse_watch() {
+        let Command::Job(JobCommand::List { watch, .. }) = parse(&["job", "list", "-w"]) else {
+            panic!("expected job list");
+        };
+        assert_eq!(
+            Some(Duration::from_secs(2)),
+            watch.interval(OutputFormat::Table).unwrap()
+        );
+        assert!(watch.interval(OutputFormat::Json).is_err());
+
+        assert!(matches!(
+            parse(&["cluster", "nodes", "--watch", "5"]),
+            Command::Cluster(ClusterCommand::Nodes {
+                watch: WatchArgs { watch: Some(5) }
+            })
+        ));
+        assert!(matches!(
+            parse(&["queues"]),
+            Command::Queues {
+                watch: WatchArgs { watch: None }
+            }
+        ));
+        assert!(Cli::try_parse_from(["agscheduler-cli", "queues", "-w", "0"]).is_err());
+    }
+
+    #[test]
+    fn it_parse_job_delete_requires_id_or_all() {
+        assert!(Cli::try_parse_from(["agscheduler-cli", "job", "delete"]).is_err());
+        assert!(Cli::try_parse_from(["agscheduler-cli", "job", "delete", "x", "--all"]).is_err());
+        assert!(matches!(
+            parse(&["job", "delete", "--all", "-y"]),
+            Command::Job(JobCommand::Delete {
+                id: None,
+                all: true,
+                yes: true
+            })
+        ));
+    }
+
+    #[test]
+    fn it_job_args_to_job() {
+        let job = JobArgs {
+            job_type: Some("cron".to_string()),
+            timezone: Some("UTC".to_string()),
+            func_name: Some("f".to_string()),
+            args: Some(r#"{"a": 1}"#.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            json!({
+                "id": "",
+                "name": "myJob",
+                "type": "cron",
+                "start_at": "",
+                "interval": "",
+                "cron_expr": "*/1 * * * *",
+                "timezone": "UTC",
+                "func_name": "f",
+                "args": {"a": 1},
+                "timeout": "1h",
+                "queues": [],
+            }),
+            job.to_job().unwrap().to_body()
+        );
+    }
+
+    #[test]
+    fn it_job_args_to_job_error() {
+        assert_eq!(
+            "`--func-name` is required",
+            JobArgs::default().to_job().unwrap_err().to_string()
+        );
+
+        let job = JobArgs {
+            func_name: Some("f".to_string()),
+            queues: Some("[".to_string()),
+            ..Default::default()
+        };
+        assert!(job
+            .to_job()
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid `--queues`"));
+    }
+
+    #[test]
+    fn it_job_args_apply_to() {
+        let job_args = JobArgs {
+            name: Some("myJob2".to_string()),
+            queues: Some(r#"["default"]"#.to_string()),
+            ..Default::default()
+        };
+        let mut job = Job {
+            name: "myJob".to_string(),
+            interval: "60s".to_string(),
+            ..Default::default()
+        };
+
+        job_args.apply_to(&mut job).unwrap();
+        assert_eq!("myJob2", job.name);
+        assert_eq!("60s", job.interval);
+        assert_eq!(vec!["default".to_string()], job.queues);
+    }
+
+    #[test]
+    fn it_set_field() {
+        let Command::Job(JobCommand::Update { set, .. }) = parse(&[
+            "job",
+            "update",
+            "x",
+            "--set",
+            "timeout=30m",
+            "--set",
+            r#"args={"a": "b=c"}"#,
+        ]) else {
+            panic!("expected `job update`");
+        };
+        let mut job = Job {
+            interval: "60s".to_string(),
+            ..Default::default()
+        };
+        for (key, value) in &set {
+            set_field(&mut job, key, value).unwrap();
+        }
+        assert_eq!("30m", job.timeout);
+        assert_eq!("60s", job.interval);
+        assert_eq!(json!({"a": "b=c"}), json!(job.args));
+
+        assert!(set_field(&mut job, "queues", "default")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid `--set queues`"));
+        for text in ["timeout", "status=paused"] {
+            assert!(
+                Cli::try_parse_from(["agscheduler-cli", "job", "update", "x", "--set", text])
+                    .is_err()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn it_run() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let id = "00227fbf671f4ed2";
+        let empty_data = json!({"data": null, "error": ""}).to_string();
+        let job = json!({
+            "args": {},
+            "cron_expr": "",
+            "end_at": "",
+            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
+            "id": id,
+            "interval": "60s",
+            "last_run_time": "0001-01-01T00:00:00Z",
+            "name": "myJob",
+            "next_run_time": "2024-04-15T04:19:12Z",
+            "queues": [],
+            "start_at": "",
+            "status": "running",
+            "timeout": "1h",
+            "timezone": "UTC",
+            "type": "interval"
+        });
+
+        let job_data = json!({"data": job, "error": ""}).to_string();
+
+        let add = server
+            .mock("POST", "/scheduler/job")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"name": "myJob", "interval": "30s"}),
+            ))
+            .with_body(&job_data)
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/scheduler/job/{}", id).as_str())
+            .with_body(&job_data)
+            .create_async()
+            .await;
+        let update = server
+            .mock("PUT", "/scheduler/job")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"id": id, "name": "myJob2", "interval": "60s"}),
+            ))
+            .with_body(&job_data)
+            .expect(2)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", format!("/scheduler/job/{}", id).as_str())
+            .with_body(&empty_data)
+            .expect(1)
+            .create_async()
+            .await;
+        let pause = server
+            .mock("POST", format!("/scheduler/job/{}/pause", id).as_str())
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        let run_job = server
+            .mock("POST", "/scheduler/job/run")
+            .match_body(mockito::Matcher::PartialJson(json!({"id": id})))
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        let start = server
+            .mock("POST", "/scheduler/start")
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        let delete_records = server
+            .mock("DELETE", "/recorder/records")
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        let first_records = server
+            .mock("GET", "/recorder/records?page=1&page_size=1")
+            .with_body(
+                json!({
+                    "data": {"page": 1, "page_size": 1, "total": 3, "res": [{"id": 1}]},
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let job_records = server
+            .mock("GET", "/recorder/records/x?page=1&page_size=100")
+            .with_body(
+                json!({
+                    "data": {"page": 1, "page_size": 100, "total": 1, "res": [
+                        {"id": 1, "job_id": "x", "status": "completed",
+                         "start_at": "2024-04-16T01:00:00Z", "end_at": "2024-04-16T01:00:02Z"}
+                    ]},
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let next_records = server
+            .mock("GET", "/recorder/records?page=2&page_size=1")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&url)).unwrap();
+        let mut mock = MockInteractionTrait::new();
+        mock.expect_confirm_delete().times(1).return_const(false);
+        mock.expect_is_terminal().return_const(true);
+        mock.expect_confirm_common()
+            .withf(|prompt| prompt == "Update job `myJob3`?")
+            .times(1)
+            .return_const(false);
+
+        for args in [
+            vec![
+                "job",
+                "add",
+                "--func-name",
+                "github.com/agscheduler/agscheduler/examples.PrintMsg",
+                "--interval",
+                "30s",
+            ],
+            vec!["job", "update", id, "--name", "myJob2", "--yes"],
+            vec!["job", "update", id, "--name", "myJob3"],
+            vec!["job", "update", id, "--set", "name=myJob2", "-y"],
+            vec!["job", "update", id, "--name", "myJob"],
+            vec!["job", "delete", id, "--yes"],
+            vec!["job", "delete", id],
+            vec!["job", "pause", id],
+            vec!["job", "run", id],
+            vec!["scheduler", "start"],
+            vec!["records", "delete", "--all", "-y"],
+            vec![
+                "records",
+                "list",
+                "--all",
+                "--page-size",
+                "1",
+                "--limit",
+                "1",
+            ],
+            vec!["stats", "--job-id", "x"],
+        ] {
+            run(&ags, parse(&args), OutputFormat::Table, &mock)
+                .await
+                .unwrap();
+        }
+
+        add.assert_async().await;
+        update.assert_async().await;
+        delete.assert_async().await;
+        pause.assert_async().await;
+        run_job.assert_async().await;
+        start.assert_async().await;
+        delete_records.assert_async().await;
+        first_records.assert_async().await;
+        job_records.assert_async().await;
+        next_records.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_run_bulk() {
+        let mut server = mockito::Server::new_async().await;
+        let empty_data = json!({"data": null, "error": ""}).to_string();
+        server
+            .mock("GET", "/scheduler/jobs")
+            .with_body(
+                json!({
+                    "data": [
+                        {"id": "a", "name": "report-1", "status": "running"},
+                        {"id": "b", "name": "report-2", "status": "paused"},
+                        {"id": "c", "name": "cleanup", "status": "running"},
+                    ],
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let mut pauses = vec![];
+        for id in ["a", "c"] {
+            pauses.push(
+                server
+                    .mock("POST", format!("/scheduler/job/{id}/pause").as_str())
+                    .with_body(&empty_data)
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let ags = AGScheduler::new(http::Config::new(&server.url())).unwrap();
+        let mut mock = MockInteractionTrait::new();
+        mock.expect_confirm_common()
+            .withf(|prompt| prompt == "pause 2 jobs?")
+            .times(2)
+            .returning({
+                let mut answers = vec![true, false].into_iter();
+                move |_| answers.next().unwrap()
+            });
+
+        for _ in 0..2 {
+            run(
+                &ags,
+                parse(&["job", "bulk", "pause", "--status", "running"]),
+                OutputFormat::Table,
+                &mock,
+            )
+            .await
+            .unwrap();
+        }
+        run(
+            &ags,
+            parse(&["job", "bulk", "delete", "--name", "nothing-*", "--yes"]),
+            OutputFormat::Table,
+            &mock,
+        )
+        .await
+        .unwrap();
+        server
+            .mock("POST", "/scheduler/job/b/resume")
+            .with_body(json!({"data": null, "error": "boom"}).to_string())
+            .create_async()
+            .await;
+        assert_eq!(
+            "1 of 1 jobs failed",
+            run(
+                &ags,
+                parse(&["job", "bulk", "resume", "--status", "paused", "-y"]),
+                OutputFormat::Json,
+                &mock
+            )
+            .await
+            .unwrap_err()
+            .to_string()
+        );
+        assert_eq!(
+            "Select the jobs with at least one filter or `--stdin`",
+            run(
+                &ags,
+                parse(&["job", "bulk", "run", "-y"]),
+                OutputFormat::Json,
+                &mock
+            )
+            .await
+            .unwrap_err()
+            .to_string()
+        );
+
+        for pause in pauses {
+            pause.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn it_run_dry_run() {
+        let mut server = mockito::Server::new_async().await;
+        let job = json!({"id": "x", "name": "myJob", "type": "interval", "interval": "60s"});
+        server
+            .mock("GET", "/scheduler/job/x")
+            .with_body(json!({"data": job, "error": ""}).to_string())
+            .create_async()
+            .await;
+        let sent = server
+            .mock("PUT", "/scheduler/job")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&server.url()).with_dry_run(true)).unwrap();
+        let mut mock = MockInteractionTrait::new();
+        mock.expect_is_terminal().return_const(true);
+        mock.expect_confirm_common().times(0);
+
+        for args in [
+            vec!["job", "update", "x", "--interval", "30s"],
+            vec!["job", "add", "--func-name", "f"],
+            vec!["scheduler", "start"],
+        ] {
+            run(&ags, parse(&args), OutputFormat::Json, &mock)
+                .await
+                .unwrap();
+        }
+
+        sent.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_run_update_not_terminal() {
+        let mut server = mockito::Server::new_async().await;
+        let job = json!({"id": "x", "name": "myJob", "type": "interval", "interval": "60s"});
+        server
+            .mock("GET", "/scheduler/job/x")
+            .with_body(json!({"data": job, "error": ""}).to_string())
+            .create_async()
+            .await;
+        let update = server
+            .mock("PUT", "/scheduler/job")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"id": "x", "interval": "30s"}),
+            ))
+            .with_body(json!({"data": job, "error": ""}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&server.url())).unwrap();
+        let mut mock = MockInteractionTrait::new();
+        mock.expect_is_terminal().return_const(false);
+        mock.expect_confirm_common().times(0);
+
+        run(
+            &ags,
+            parse(&["job", "update", "x", "--interval", "30s"]),
+            OutputFormat::Json,
+            &mock,
+        )
+        .await
+        .unwrap();
+
+        update.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_run_error() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        server
+            .mock("GET", "/info")
+            .with_body(json!({"data": null, "error": "unauthorized"}).to_string())
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&url)).unwrap();
+        let mock = MockInteractionTrait::new();
+
+        let err = run(&ags, parse(&["info"]), OutputFormat::Json, &mock)
+            .await
+            .unwrap_err();
+        assert_eq!("unauthorized", err.to_string());
+    }
+
+    #[tokio::test]
+    async fn it_run_apply() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let existing = json!({
+            "id": "00227fbf671f4ed2", "name": "myJob", "type": "interval", "interval": "60s",
+            "timezone": "UTC", "func_name": "f", "args": {}, "timeout": "1h", "queues": []
+        });
+        server
+            .mock("GET", "/scheduler/jobs")
+            .with_body(json!({"data": [existing], "error": ""}).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+        let update = server
+            .mock("PUT", "/scheduler/job")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"id": "00227fbf671f4ed2", "interval": "30s"}),
+            ))
+            .with_body(json!({"data": existing, "error": ""}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", "/scheduler/job")
+            .match_body(mockito::Matcher::PartialJson(json!({"name": "newJob"})))
+            .with_body(json!({"data": existing, "error": ""}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let path =
+            env::temp_dir().join(format!("agscheduler-cli-apply-{}.yaml", std::process::id()));
+        fs::write(
+            &path,
+            "- {name: myJob, func_name: f, timezone: UTC, interval: 30s}\n- {name: newJob, func_name: f, timezone: UTC}\n",
+        )
+        .unwrap();
+        let file = path.to_str().unwrap();
+
+        let ags = AGScheduler::new(http::Config::new(&url)).unwrap();
+        let dry_run = AGScheduler::new(http::Config::new(&url).with_dry_run(true)).unwrap();
+        let mock = MockInteractionTrait::new();
+        run(
+            &dry_run,
+            parse(&["apply", "-f", file, "--dry-run"]),
+            OutputFormat::Table,
+            &mock,
+        )
+        .await
+        .unwrap();
+        run(
+            &ags,
+            parse(&["apply", "-f", file]),
+            OutputFormat::Json,
+            &mock,
+        )
+        .await
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        update.assert_async().await;
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_run_export_import() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let paused = json!({
+            "id": "00227fbf671f4ed2", "name": "myJob", "type": "interval", "interval": "60s",
+            "func_name": "f", "status": "paused"
+        });
+        server
+            .mock("GET", "/scheduler/jobs")
+            .with_body(json!({"data": [paused], "error": ""}).to_string())
+            .create_async()
+            .await;
+        let add = server
+            .mock("POST", "/scheduler/job")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"id": "", "name": "myJob"}),
+            ))
+            .with_body(
+                json!({"data": {"id": "e99532afe9f44e63", "name": "myJob"}, "error": ""})
+                    .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let pause = server
+            .mock("POST", "/scheduler/job/e99532afe9f44e63/pause")
+            .with_body(json!({"data": null, "error": ""}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let path = env::temp_dir().join(format!(
+            "agscheduler-cli-export-{}.yaml",
+            std::process::id()
+        ));
+        let file = path.to_str().unwrap();
+
+        let ags = AGScheduler::new(http::Config::new(&url)).unwrap();
+        let mock = MockInteractionTrait::new();
+        run(
+            &ags,
+            parse(&["export", "-f", file]),
+            OutputFormat::Table,
+            &mock,
+        )
+        .await
+        .unwrap();
+        let bundle = Bundle::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!("paused", bundle.jobs[0].status);
+        assert!(fs::read_to_string(&path).unwrap().starts_with("version: 1"));
+
+        run(
+            &ags,
+            parse(&["import", "-f", file, "--new-ids", "--restore-paused"]),
+            OutputFormat::Table,
+            &mock,
+        )
+        .await
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        add.assert_async().await;
+        pause.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_import_jobs_failed_new_ids() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/scheduler/job")
+            .with_body(json!({"data": null, "error": "boom"}).to_string())
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&server.url())).unwrap();
+        let jobs: Vec<Job> =
+            serde_json::from_value(json!([{"id": "00227fbf671f4ed2", "name": "myJob"}])).unwrap();
+        assert_eq!(
+            vec![JobResult {
+                id: "00227fbf671f4ed2".to_string(),
+                name: "myJob".to_string(),
+                result: "boom".to_string(),
+            }],
+            import_jobs(&ags, jobs, true, false).await
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+
+use crate::duration;
+use crate::timezone;
+
+/// Name, bounds and value names of each field, in the order of a 6 field expression.
+const FIELDS: [(&str, u32, u32, &[&str]); 6] = [
+    ("Second", 0, 59, &[]),
+    ("Minute", 0, 59, &[]),
+    ("Hour", 0, 23, &[]),
+    ("Day of month", 1, 31, &[]),
+    (
+        "Month",
+        1,
+        12,
+        &[
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ],
+    ),
+    (
+        "Day of week",
+        0,
+        6,
+        &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+    ),
+];
+
+/// Upper bound of the search for the next fire time, an impossible date like Feb 30 never fires.
+const MAX_YEARS: i32 = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Schedule {
+    /// Allowed values of each field as bits, and whether day of month and day of week are `*`.
+    Fields {
+        bits: [u64; 6],
+        dom_star: bool,
+        dow_star: bool,
+    },
+    Every(Duration),
+}
+
+/// A cron expression as accepted by the scheduler.
+///
+/// Five fields, or six with leading seconds, `@yearly`, `@annually`, `@monthly`, `@weekly`,
+/// `@daily`, `@midnight`, `@hourly`, `@every <duration>`, and an optional `TZ=`/`CRON_TZ=` prefix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronExpr {
+    schedule: Schedule,
+    /// Timezone of the `TZ=` prefix, taking precedence over the job timezone.
+    pub timezone: Option<Tz>,
+}
+
+fn parse_value(field: usize, text: &str) -> anyhow::Result<u32> {
+    let (name, min, max, names) = FIELDS[field];
+    let value = match names.iter().position(|n| n.eq_ignore_ascii_case(text)) {
+        Some(i) => i as u32 + min,
+        None => text.parse().map_err(|_| {
+            anyhow::anyhow!("{name} value `{text}` is not a number or a known name")
+        })?,
+    };
+    if value < min || value > max {
+        return Err(anyhow::anyhow!(
+            "{name} value `{value}` is out of range {min}-{max}"
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Parses one field like `*/5`, `1-10/2`, `MON-FRI` or `0,30`, returning its bits and whether it starts with `*`.
+fn parse_field(field: usize, text: &str) -> anyhow::Result<(u64, bool)> {
+    let (name, min, max, _) = FIELDS[field];
+    let mut bits = 0;
+    let mut star = false;
+
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("{name} step `{step}` is not a number"))?;
+                if step == 0 {
+                    return Err(anyhow::anyhow!("{name} step must be greater than 0"));
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (start, end) = match range {
+            "*" | "?" => {
+                // Like robfig/cron, `*/1` still counts as `*` for the day fields.
+                star = step.is_none_or(|step| step == 1);
+                (min, max)
+            }
+            _ => match range.split_once('-') {
+                Some((start, end)) => (parse_value(field, start)?, parse_value(field, end)?),
+                None => {
+                    let start = parse_value(field, range)?;
+                    (start, if step.is_some() { max } else { start })
+                }
+            },
+        };
+        if start > end {
+            return Err(anyhow::anyhow!(
+                "{name} range `{range}` starts after it ends"
+            ));
+        }
+
+        let step = step.unwrap_or(1);
+        let mut value = start;
+        while value <= end {
+            bits |= 1 << value;
+            value += step;
+        }
+    }
+
+    Ok((bits, star))
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> anyhow::Result<Self> {
+        let mut expr = expr.trim();
+        let mut timezone = None;
+        for prefix in ["TZ=", "CRON_TZ="] {
+            if let Some(rest) = expr.strip_prefix(prefix) {
+                let (name, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                timezone = Some(timezone::parse(name)?);
+                expr = rest.trim();
+            }
+        }
+
+        let fields = match expr {
+            "@yearly" | "@annually" => "0 0 0 1 1 *",
+            "@monthly" => "0 0 0 1 * *",
+            "@weekly" => "0 0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 0 * * *",
+            "@hourly" => "0 0 * * * *",
+            _ => expr,
+        };
+        if let Some(every) = fields.strip_prefix("@every ") {
+            let every = duration::parse(every)?;
+            if every < Duration::from_secs(1) {
+                return Err(anyhow::anyhow!("`@every` needs at least 1s"));
+            }
+            return Ok(CronExpr {
+                schedule: Schedule::Every(Duration::from_secs(every.as_secs())),
+                timezone,
+            });
+        }
+        if fields.starts_with('@') {
+            return Err(anyhow::anyhow!(
+                "Unknown descriptor `{fields}`, expected @yearly, @annually, @monthly, @weekly, @daily, @midnight, @hourly or @every <duration>"
+            ));
+        }
+
+        let mut parts: Vec<&str> = fields.split_whitespace().collect();
+        match parts.len() {
+            5 => parts.insert(0, "0"),
+            6 => {}
+            n => {
+                return Err(anyhow::anyhow!(
+                    "Expected 5 fields (minute hour day-of-month month day-of-week) or 6 with leading seconds, found {n}"
+                ))
+            }
+        }
+
+        let mut bits = [0; 6];
+        let mut stars = [false; 6];
+        for (i, part) in parts.iter().enumerate() {
+            (bits[i], stars[i]) = parse_field(i, part)?;
+        }
+
+        Ok(CronExpr {
+            schedule: Schedule::Fields {
+                bits,
+                dom_star: stars[3],
+                dow_star: stars[5],
+            },
+            timezone,
+        })
+    }
+
+    /// Returns the next `count` fire times after `after`, in the `TZ=` timezone if set, or `tz`.
+    pub fn next_times(&self, after: DateTime<Utc>, tz: Tz, count: usize) -> Vec<DateTime<Tz>> {
+        let tz = self.timezone.unwrap_or(tz);
+        let mut times = vec![];
+        let mut t = after.with_timezone(&tz);
+        while times.len() < count {
+            match self.next(t) {
+                Some(next) => {
+                    times.push(next);
+                    t = next;
+                }
+                None => break,
+            }
+        }
+
+        times
+    }
+
+    fn next(&self, after: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let (bits, dom_star, dow_star) = match &self.schedule {
+            Schedule::Every(every) => {
+                let t = after.with_nanosecond(0)? + *every;
+                return Some(t);
+            }
+            Schedule::Fields {
+                bits,
+                dom_star,
+                dow_star,
+            } => (bits, *dom_star, *dow_star),
+        };
+        let has = |field: usize, value: u32| bits[field] & (1 << value) != 0;
+        let day_matches = |t: &NaiveDateTime| {
+            let dom = has(3, t.day());
+            let dow = has(5, t.weekday().num_days_from_sunday());
+            match dom_star || dow_star {
+                true => dom && dow,
+                false => dom || dow,
+            }
+        };
+
+        let tz = after.timezone();
+        let mut t = after.naive_local().with_nanosecond(0)? + chrono::Duration::seconds(1);
+        let year_limit = t.year() + MAX_YEARS;
+
+        loop {
+            if t.year() > year_limit {
+                return None;
+            }
+            if !has(4, t.month()) {
+                let (year, month) = match t.month() {
+                    12 => (t.year() + 1, 1),
+                    m => (t.year(), m + 1),
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !day_matches(&t) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !has(2, t.hour()) {
+                t = t.with_minute(0)?.with_second(0)? + chrono::Duration::hours(1);
+                continue;
+            }
+            if !has(1, t.minute()) {
+                t = t.with_second(0)? + chrono::Duration::minutes(1);
+                continue;
+            }
+            if !has(0, t.second()) {
+                t += chrono::Duration::seconds(1);
+                continue;
+            }
+
+            match tz.from_local_datetime(&t) {
+                LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => return Some(time),
+                // Skipped by a DST change.
+                LocalResult::None => t += chrono::Duration::seconds(1),
+            }
+        }
+    }
+}
+
+/// Parses `expr` and returns its next `count` fire times in `timezone`, an IANA name.
+pub fn next_fire_times(
+    expr: &str,
+    timezone: &str,
+    count: usize,
+) -> anyhow::Result<Vec<DateTime<Tz>>> {
+    let tz = timezone::parse(timezone)?;
+
+    Ok(CronExpr::parse(expr)?.next_times(Utc::now(), tz, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str, tz: Tz, count: usize) -> Vec<String> {
+        CronExpr::parse(expr)
+            .unwrap()
+            .next_times(utc(after), tz, count)
+            .iter()
+            .map(|t| t.to_rfc3339())
+            .collect()
+    }
+
+    #[test]
+    fn it_next_fire_times() {
+        assert_eq!(3, next_fire_times("@hourly", "UTC", 3).unwrap().len());
+        assert!(next_fire_times("@hourly", "Mars/Base", 3).is_err());
+        assert!(next_fire_times("@hour", "UTC", 3).is_err());
+    }
+
+    #[test]
+    fn it_parse_errors() {
+        for (expr, err) in [
+            ("* * * *", "Expected 5 fields (minute hour day-of-month month day-of-week) or 6 with leading seconds, found 4"),
+            ("61 * * * *", "Minute value `61` is out of range 0-59"),
+            ("* * 0 * *", "Day of month value `0` is out of range 1-31"),
+            ("* * * FOO *", "Month value `FOO` is not a number or a known name"),
+            ("*/0 * * * *", "Minute step must be greater than 0"),
+            ("* 10-2 * * *", "Hour range `10-2` starts after it ends"),
+            ("@often", "Unknown descriptor `@often`, expected @yearly, @annually, @monthly, @weekly, @daily, @midnight, @hourly or @every <duration>"),
+            ("@every 1d", "Invalid duration `1d`, unknown unit `d` (ns, us, ms, s, m, h)"),
+            ("@every 10ms", "`@every` needs at least 1s"),
+            ("TZ=Mars/Base * * * * *", "Unknown timezone `Mars/Base`"),
+        ] {
+            assert_eq!(err, CronExpr::parse(expr).unwrap_err().to_string(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn it_next_times() {
+        let after = "2024-04-15T04:19:12Z";
+        assert_eq!(
+            vec!["2024-04-15T04:20:00+00:00", "2024-04-15T04:25:00+00:00"],
+            next("*/5 * * * *", after, Tz::UTC, 2)
+        );
+        assert_eq!(
+            vec!["2024-04-15T04:19:15+00:00", "2024-04-15T04:19:30+00:00"],
+            next("*/15 * * * * *", after, Tz::UTC, 2)
+        );
+        assert_eq!(
+            vec!["2024-04-16T00:00:00+08:00"],
+            next("@daily", after, Tz::Asia__Shanghai, 1)
+        );
+        assert_eq!(
+            vec!["2024-04-15T04:19:42+00:00", "2024-04-15T04:20:12+00:00"],
+            next("@every 30s", after, Tz::UTC, 2)
+        );
+        assert_eq!(
+            vec!["2024-04-15T09:00:00+00:00", "2024-04-19T09:00:00+00:00"],
+            next("0 9 * * MON,fri", after, Tz::UTC, 2)
+        );
+        // Day of month and day of week are ORed when both are restricted.
+        assert_eq!(
+            vec![
+                "2024-04-20T00:00:00+00:00",
+                "2024-04-27T00:00:00+00:00",
+                "2024-05-01T00:00:00+00:00"
+            ],
+            next("0 0 1 * SAT", after, Tz::UTC, 3)
+        );
+        assert_eq!(
+            vec!["2024-04-15T22:00:00-04:00"],
+            next("TZ=America/New_York 0 22 * * *", after, Tz::UTC, 1)
+        );
+        assert!(next("0 0 30 2 *", after, Tz::UTC, 1).is_empty());
+        assert_eq!(
+            next("0 0 13 * *", after, Tz::UTC, 3),
+            next("0 0 13 * */1", after, Tz::UTC, 3)
+        );
+        assert_eq!(
+            next("0 0 * * FRI", after, Tz::UTC, 3),
+            next("0 0 */1 * FRI", after, Tz::UTC, 3)
+        );
+    }
+}
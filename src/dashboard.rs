@@ -0,0 +1,603 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::ExecutableCommand;
+use futures::StreamExt;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table, TableState};
+use ratatui::{Frame, Terminal};
+
+use crate::api_client::AGScheduler;
+use crate::datetime;
+use crate::models::{ClusterNode, Info, Job, Queue, Record};
+
+/// Number of records shown for the selected job.
+const RECENT_RECORDS: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pane {
+    Jobs,
+    Queues,
+    Nodes,
+}
+
+/// Operations triggered by a key, run against the selected job.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Quit,
+    Refresh,
+    Pause,
+    Resume,
+    Run,
+    Delete,
+}
+
+/// State of the dashboard, drawn by `draw` and changed by `handle_key` and `refresh`.
+#[derive(Default)]
+struct App {
+    info: Option<Info>,
+    jobs: Vec<Job>,
+    queues: Vec<Queue>,
+    nodes: Vec<ClusterNode>,
+    records: Vec<Record>,
+    /// ID of the job `records` belong to.
+    records_job_id: String,
+    jobs_state: TableState,
+    queues_state: TableState,
+    nodes_state: TableState,
+    focus: Option<Pane>,
+    /// Set while waiting for `y` to confirm a deletion.
+    confirm_delete: bool,
+    message: String,
+}
+
+fn select_next(state: &mut TableState, len: usize, step: isize) {
+    if len == 0 {
+        state.select(None);
+        return;
+    }
+    let current = state.selected().unwrap_or(0) as isize;
+    state.select(Some((current + step).clamp(0, len as isize - 1) as usize));
+}
+
+impl App {
+    fn focus(&self) -> Pane {
+        self.focus.unwrap_or(Pane::Jobs)
+    }
+
+    fn selected_job(&self) -> Option<&Job> {
+        self.jobs_state.selected().and_then(|i| self.jobs.get(i))
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let code = key.code;
+        // Raw mode turns Ctrl-C into a key instead of a signal.
+        if key.modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
+            return Some(Action::Quit);
+        }
+        if self.confirm_delete {
+            self.confirm_delete = false;
+            if code == KeyCode::Char('y') {
+                return Some(Action::Delete);
+            }
+            self.message = "Delete cancelled".to_string();
+            return None;
+        }
+
+        let step = match code {
+            KeyCode::Up | KeyCode::Char('k') => -1,
+            KeyCode::Down | KeyCode::Char('j') => 1,
+            KeyCode::PageUp => -10,
+            KeyCode::PageDown => 10,
+            KeyCode::Tab => {
+                self.focus = Some(match self.focus() {
+                    Pane::Jobs => Pane::Queues,
+                    Pane::Queues => Pane::Nodes,
+                    Pane::Nodes => Pane::Jobs,
+                });
+                return None;
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Char('g') => return Some(Action::Refresh),
+            KeyCode::Char('p') => return Some(Action::Pause),
+            KeyCode::Char('r') => return Some(Action::Resume),
+            KeyCode::Char('x') => return Some(Action::Run),
+            KeyCode::Char('d') => {
+                if let Some(job) = self.selected_job() {
+                    self.message = format!("Delete job `{}`? (y/N)", job.name);
+                    self.confirm_delete = true;
+                }
+                return None;
+            }
+            _ => return None,
+        };
+
+        match self.focus() {
+            Pane::Jobs => select_next(&mut self.jobs_state, self.jobs.len(), step),
+            Pane::Queues => select_next(&mut self.queues_state, self.queues.len(), step),
+            Pane::Nodes => select_next(&mut self.nodes_state, self.nodes.len(), step),
+        }
+
+        None
+    }
+
+    /// Reloads every pane, keeping the selected job by ID.
+    async fn refresh(&mut self, ags: &AGScheduler) -> anyhow::Result<()> {
+        let selected_id = self.selected_job().map(|j| j.id.to_string());
+
+        let (info, jobs, queues) = tokio::join!(ags.get_info(), ags.list_jobs(), ags.list_queues());
+        let info = info?;
+        self.nodes = match info.is_cluster_mode {
+            true => ags.list_cluster_nodes().await?,
+            false => vec![],
+        };
+        self.info = Some(info);
+        self.jobs = jobs?;
+        self.queues = queues?;
+
+        let index = selected_id
+            .and_then(|id| self.jobs.iter().position(|j| j.id == id))
+            .unwrap_or(0);
+        self.jobs_state
+            .select((!self.jobs.is_empty()).then_some(index.min(self.jobs.len() - 1)));
+        select_next(&mut self.queues_state, self.queues.len(), 0);
+        select_next(&mut self.nodes_state, self.nodes.len(), 0);
+
+        self.refresh_records(ags).await
+    }
+
+    async fn refresh_records(&mut self, ags: &AGScheduler) -> anyhow::Result<()> {
+        let Some(job_id) = self.selected_job().map(|j| j.id.to_string()) else {
+            self.records = vec![];
+            self.records_job_id = String::new();
+            return Ok(());
+        };
+
+        self.records = ags.list_records(&job_id, 1, RECENT_RECORDS).await?.res;
+        self.records_job_id = job_id;
+
+        Ok(())
+    }
+
+    async fn apply(&mut self, ags: &AGScheduler, action: Action) -> anyhow::Result<()> {
+        if action == Action::Refresh {
+            return self.refresh(ags).await;
+        }
+        let Some(job) = self.selected_job().cloned() else {
+            return Ok(());
+        };
+
+        match action {
+            Action::Pause => ags.pause_job(&job.id).await?,
+            Action::Resume => ags.resume_job(&job.id).await?,
+            Action::Run => ags.run_job(&job).await?,
+            Action::Delete => ags.delete_job(&job.id).await?,
+            Action::Quit | Action::Refresh => {}
+        }
+        self.message = format!("{:?} `{}`: Ok", action, job.name);
+
+        self.refresh(ags).await
+    }
+
+    fn block(&self, title: &str, pane: Option<Pane>) -> Block<'static> {
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string());
+        if pane.is_some() && pane == Some(self.focus()) {
+            block = block.border_style(Style::default().fg(Color::Cyan));
+        }
+        block
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [title, body, bottom, footer] = split(
+            Direction::Vertical,
+            frame.size(),
+            [
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(8),
+                Constraint::Length(1),
+            ],
+        );
+        let [jobs, side] = split(
+            Direction::Horizontal,
+            body,
+            [Constraint::Percentage(60), Constraint::Percentage(40)],
+        );
+        let [details, records] = split(
+            Direction::Vertical,
+            side,
+            [Constraint::Length(13), Constraint::Min(3)],
+        );
+        let [queues, nodes] = split(
+            Direction::Horizontal,
+            bottom,
+            [Constraint::Percentage(40), Constraint::Percentage(60)],
+        );
+
+        let status = match &self.info {
+            Some(info) => format!(
+                "AGScheduler {}  {}{}",
+                info.version,
+                if info.is_running {
+                    "running"
+                } else {
+                    "stopped"
+                },
+                if info.is_cluster_mode {
+                    "  cluster"
+                } else {
+                    ""
+                }
+            ),
+            None => "AGScheduler".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(status).style(Style::default().add_modifier(Modifier::BOLD)),
+            title,
+        );
+
+        self.draw_jobs(frame, jobs);
+        self.draw_details(frame, details);
+        self.draw_records(frame, records);
+        self.draw_queues(frame, queues);
+        self.draw_nodes(frame, nodes);
+
+        let help = "↑↓ select  Tab pane  p pause  r resume  x run  d delete  g refresh  q quit";
+        let text = match self.message.is_empty() {
+            true => help.to_string(),
+            false => format!("{}  |  {}", self.message, help),
+        };
+        frame.render_widget(Paragraph::new(text), footer);
+    }
+
+    fn draw_jobs(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.jobs.iter().map(|j| {
+            Row::new(vec![
+                j.name.to_string(),
+                j.job_type.to_string(),
+                j.type_value().to_string(),
+                datetime::format_local(j.next_run_time.as_deref()),
+                j.status.to_string(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(25),
+                Constraint::Length(8),
+                Constraint::Percentage(20),
+                Constraint::Length(19),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(vec!["Name", "Type", "TypeValue", "NextRunTime", "Status"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(self.block(&format!("Jobs ({})", self.jobs.len()), Some(Pane::Jobs)))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.jobs_state);
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let lines = match self.selected_job() {
+            Some(j) => vec![
+                Line::from(format!("ID        {}", j.id)),
+                Line::from(format!("Name      {}", j.name)),
+                Line::from(format!("Type      {} {}", j.job_type, j.type_value())),
+                Line::from(format!("Timezone  {}", j.timezone)),
+                Line::from(format!("Func      {}", j.func_name)),
+                Line::from(format!("Args      {}", serde_json::json!(j.args))),
+                Line::from(format!("Timeout   {}", j.timeout)),
+                Line::from(format!("Queues    {}", j.queues.join(", "))),
+                Line::from(format!(
+                    "LastRun   {}",
+                    datetime::format_local(j.last_run_time.as_deref())
+                )),
+                Line::from(format!(
+                    "NextRun   {}",
+                    datetime::format_local(j.next_run_time.as_deref())
+                )),
+                Line::from(format!("Status    {}", j.status)),
+            ],
+            None => vec![Line::from("No job selected")],
+        };
+
+        frame.render_widget(Paragraph::new(lines).block(self.block("Job", None)), area);
+    }
+
+    fn draw_records(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.records.iter().map(|r| {
+            let style = match r.status.as_str() {
+                "error" | "timeout" => Style::default().fg(Color::Red),
+                "running" => Style::default().fg(Color::Yellow),
+                _ => Style::default(),
+            };
+            Row::new(vec![
+                datetime::format_local(r.start_at.as_deref()),
+                r.status.to_string(),
+                r.result.to_string(),
+            ])
+            .style(style)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(19),
+                Constraint::Length(8),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(vec!["StartAt", "Status", "Result"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(self.block("Recent records", None));
+
+        frame.render_widget(table, area);
+    }
+
+    fn draw_queues(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.queues.iter().map(|q| {
+            Row::new(vec![
+                q.name.to_string(),
+                q.queue_type.to_string(),
+                q.count.to_string(),
+                q.workers.to_string(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(vec!["Name", "Type", "Count", "Workers"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(self.block("Queues", Some(Pane::Queues)))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.queues_state);
+    }
+
+    fn draw_nodes(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.nodes.iter().map(|n| {
+            Row::new(vec![
+                n.endpoint.to_string(),
+                if n.is_leader() { "leader" } else { "" }.to_string(),
+                n.queue.to_string(),
+                n.health.to_string(),
+                datetime::format_local(n.last_heartbeat_time.as_deref()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(30),
+                Constraint::Length(7),
+                Constraint::Percentage(20),
+                Constraint::Length(7),
+                Constraint::Length(19),
+            ],
+        )
+        .header(
+            Row::new(vec!["Endpoint", "Role", "Queue", "Health", "LastHeartbeat"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(self.block("Cluster nodes", Some(Pane::Nodes)))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.nodes_state);
+    }
+}
+
+fn split<const N: usize>(
+    direction: Direction,
+    area: Rect,
+    constraints: [Constraint; N],
+) -> [Rect; N] {
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area);
+
+    std::array::from_fn(|i| chunks[i])
+}
+
+/// Leaves raw mode and the alternate screen when dropped, also on errors.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> anyhow::Result<Self> {
+        terminal::enable_raw_mode()?;
+        io::stdout().execute(EnterAlternateScreen)?;
+
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = io::stdout().execute(LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Opens the full-screen dashboard, refreshing every `interval` until `q` or Ctrl-C is pressed.
+pub async fn run(ags: &AGScheduler, interval: Duration) -> anyhow::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+
+    let mut app = App::default();
+    if let Err(err) = app.refresh(ags).await {
+        app.message = format!("Error: {err}");
+    }
+    let mut refreshed_at = Instant::now();
+    let mut events = EventStream::new();
+
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        let timeout = interval.saturating_sub(refreshed_at.elapsed());
+        let action = tokio::select! {
+            event = events.next() => match event.transpose()? {
+                Some(Event::Key(key)) if key.kind == KeyEventKind::Press => app.handle_key(key),
+                Some(_) => None,
+                None => Some(Action::Quit),
+            },
+            _ = tokio::time::sleep(timeout) => Some(Action::Refresh),
+        };
+
+        let result = match action {
+            Some(Action::Quit) => return Ok(()),
+            Some(action) => {
+                refreshed_at = Instant::now();
+                app.apply(ags, action).await
+            }
+            None if app.selected_job().map(|j| &j.id) != Some(&app.records_job_id) => {
+                app.refresh_records(ags).await
+            }
+            None => Ok(()),
+        };
+        if let Err(err) = result {
+            app.message = format!("Error: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use serde_json::json;
+
+    use crate::http;
+
+    fn job(id: &str, name: &str) -> Job {
+        serde_json::from_value(json!({
+            "id": id, "name": name, "type": "interval", "interval": "60s", "status": "running"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn it_handle_key() {
+        let mut app = App {
+            jobs: vec![job("1", "myJob1"), job("2", "myJob2")],
+            ..Default::default()
+        };
+        app.jobs_state.select(Some(0));
+
+        assert_eq!(None, app.handle_key(KeyCode::Down.into()));
+        assert_eq!("2", app.selected_job().unwrap().id);
+        assert_eq!(None, app.handle_key(KeyCode::Down.into()));
+        assert_eq!("2", app.selected_job().unwrap().id);
+        assert_eq!(
+            Some(Action::Pause),
+            app.handle_key(KeyCode::Char('p').into())
+        );
+        assert_eq!(Some(Action::Run), app.handle_key(KeyCode::Char('x').into()));
+
+        assert_eq!(None, app.handle_key(KeyCode::Char('d').into()));
+        assert!(app.message.contains("myJob2"));
+        assert_eq!(None, app.handle_key(KeyCode::Char('n').into()));
+        assert_eq!(None, app.handle_key(KeyCode::Char('d').into()));
+        assert_eq!(
+            Some(Action::Delete),
+            app.handle_key(KeyCode::Char('y').into())
+        );
+
+        assert_eq!(None, app.handle_key(KeyCode::Tab.into()));
+        assert_eq!(Pane::Queues, app.focus());
+        assert_eq!(None, app.handle_key(KeyCode::Up.into()));
+        assert_eq!("2", app.selected_job().unwrap().id);
+        assert_eq!(
+            Some(Action::Quit),
+            app.handle_key(KeyCode::Char('q').into())
+        );
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        app.confirm_delete = true;
+        assert_eq!(Some(Action::Quit), app.handle_key(ctrl_c));
+    }
+
+    #[tokio::test]
+    async fn it_refresh_and_draw() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        server
+            .mock("GET", "/info")
+            .with_body(
+                json!({"data": {"is_cluster_mode": false, "is_running": true, "version": "0.6.1"}, "error": ""})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/scheduler/jobs")
+            .with_body(json!({"data": [job("1", "myJob1")], "error": ""}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/broker/queues")
+            .with_body(
+                json!({"data": [{"name": "default", "type": "Memory", "count": 1, "workers": 2}], "error": ""})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/recorder/records/1?page=1&page_size=10")
+            .with_body(
+                json!({
+                    "data": {
+                        "page": 1, "page_size": 10, "total": 1,
+                        "res": [{"id": 1, "job_id": "1", "status": "error", "result": "boom"}]
+                    },
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let pause = server
+            .mock("POST", "/scheduler/job/1/pause")
+            .with_body(json!({"data": null, "error": ""}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let ags = AGScheduler::new(http::Config::new(&url)).unwrap();
+        let mut app = App::default();
+        app.refresh(&ags).await.unwrap();
+        assert_eq!("1", app.records_job_id);
+        app.apply(&ags, Action::Pause).await.unwrap();
+        pause.assert_async().await;
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        for text in ["0.6.1", "myJob1", "default", "boom", "Pause `myJob1`: Ok"] {
+            assert!(screen.contains(text), "missing `{text}`");
+        }
+    }
+}
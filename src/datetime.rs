@@ -1,4 +1,4 @@
-use chrono::{DateTime, Local, ParseError, TimeZone};
+use chrono::{DateTime, Datelike, Local, ParseError, TimeZone, Utc};
 
 pub fn parse_iso8601_to_local(iso8601_str: &str) -> Result<DateTime<Local>, ParseError> {
     let utc_datetime = DateTime::parse_from_rfc3339(iso8601_str)?;
@@ -8,6 +8,23 @@ pub fn parse_iso8601_to_local(iso8601_str: &str) -> Result<DateTime<Local>, Pars
     Ok(local_datetime)
 }
 
+/// Parses an optional RFC 3339 time of the server, `None` if it is missing, invalid,
+/// or the zero time `0001-01-01T00:00:00Z` of jobs that never ran.
+pub fn parse_utc(text: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+        .filter(|t| t.year() > 1)
+}
+
+/// Formats an optional ISO 8601 time in local time, empty if it is missing or invalid.
+pub fn format_local(iso8601_str: Option<&str>) -> String {
+    match iso8601_str.map(parse_iso8601_to_local) {
+        Some(Ok(local_datetime)) => local_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        _ => String::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,4 +38,28 @@ mod tests {
 
         assert_eq!(local_datetime, parse_iso8601_to_local(iso8601_str).unwrap());
     }
+
+    #[test]
+    fn it_parse_utc() {
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2024, 4, 13, 14, 35, 55).unwrap()),
+            parse_utc(Some("2024-04-13T22:35:55+08:00"))
+        );
+        assert_eq!(None, parse_utc(Some("0001-01-01T00:00:00Z")));
+        assert_eq!(None, parse_utc(Some("")));
+        assert_eq!(None, parse_utc(None));
+    }
+
+    #[test]
+    fn it_format_local() {
+        let utc_datetime = Utc.with_ymd_and_hms(2024, 4, 13, 14, 35, 55).unwrap();
+        let local_datetime = Local.from_utc_datetime(&utc_datetime.naive_utc());
+
+        assert_eq!(
+            local_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            format_local(Some("2024-04-13T14:35:55Z"))
+        );
+        assert_eq!("", format_local(Some("")));
+        assert_eq!("", format_local(None));
+    }
 }
@@ -0,0 +1,138 @@
+use serde_json::Value;
+
+use crate::models::Job;
+
+/// A difference between two job bodies, at a dotted path like `args.key`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldChange {
+    Added {
+        path: String,
+        new: Value,
+    },
+    Removed {
+        path: String,
+        old: Value,
+    },
+    Changed {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+fn join(path: &str, key: &str) -> String {
+    match path {
+        "" => key.to_string(),
+        _ => format!("{path}.{key}"),
+    }
+}
+
+/// Objects are compared key by key and arrays item by item regardless of order,
+/// anything else is compared as a whole.
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                match new.get(key) {
+                    Some(new_value) => diff_values(&join(path, key), old_value, new_value, changes),
+                    None => changes.push(FieldChange::Removed {
+                        path: join(path, key),
+                        old: old_value.clone(),
+                    }),
+                }
+            }
+            for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                changes.push(FieldChange::Added {
+                    path: join(path, key),
+                    new: new_value.clone(),
+                });
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for item in old.iter().filter(|item| !new.contains(item)) {
+                changes.push(FieldChange::Removed {
+                    path: path.to_string(),
+                    old: item.clone(),
+                });
+            }
+            for item in new.iter().filter(|item| !old.contains(item)) {
+                changes.push(FieldChange::Added {
+                    path: path.to_string(),
+                    new: item.clone(),
+                });
+            }
+        }
+        _ if old != new => changes.push(FieldChange::Changed {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Compares the bodies sent to the scheduler, descending into `args` and `queues`.
+pub fn jobs(old: &Job, new: &Job) -> Vec<FieldChange> {
+    let mut changes = vec![];
+    diff_values("", &old.to_body(), &new.to_body(), &mut changes);
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_jobs() {
+        let old: Job = serde_json::from_value(json!({
+            "id": "x",
+            "interval": "60s",
+            "args": {"a": 1, "b": {"c": 2}, "d": 3},
+            "queues": ["default", "slow"],
+        }))
+        .unwrap();
+        assert!(jobs(&old, &old).is_empty());
+
+        let new: Job = serde_json::from_value(json!({
+            "id": "x",
+            "interval": "30s",
+            "args": {"a": 1, "b": {"c": 4}, "e": [5]},
+            "queues": ["slow", "fast"],
+        }))
+        .unwrap();
+        assert_eq!(
+            vec![
+                FieldChange::Changed {
+                    path: "args.b.c".to_string(),
+                    old: json!(2),
+                    new: json!(4),
+                },
+                FieldChange::Removed {
+                    path: "args.d".to_string(),
+                    old: json!(3),
+                },
+                FieldChange::Added {
+                    path: "args.e".to_string(),
+                    new: json!([5]),
+                },
+                FieldChange::Changed {
+                    path: "interval".to_string(),
+                    old: json!("60s"),
+                    new: json!("30s"),
+                },
+                FieldChange::Removed {
+                    path: "queues".to_string(),
+                    old: json!("default"),
+                },
+                FieldChange::Added {
+                    path: "queues".to_string(),
+                    new: json!("fast"),
+                },
+            ],
+            jobs(&old, &new)
+        );
+    }
+}
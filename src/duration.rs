@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+/// Parses a Go duration like `1h30m`, `1.5s` or `300ms`, as accepted by the scheduler.
+///
+/// Negative durations are rejected, none of the job fields accept them.
+pub fn parse(text: &str) -> anyhow::Result<Duration> {
+    let s = text.trim();
+    if s.is_empty() {
+        return Err(anyhow::anyhow!("Empty duration"));
+    }
+    if s.starts_with('-') {
+        return Err(anyhow::anyhow!("Duration `{s}` must not be negative"));
+    }
+    let s = s.strip_prefix('+').unwrap_or(s);
+    if s == "0" {
+        return Ok(Duration::ZERO);
+    }
+
+    let mut nanos: f64 = 0.0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number = &rest[..number_len];
+        if number.is_empty() || number == "." {
+            return Err(anyhow::anyhow!(
+                "Invalid duration `{s}`, expected a number before `{rest}`"
+            ));
+        }
+        let value: f64 = number
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid duration `{s}`, bad number `{number}`"))?;
+        rest = &rest[number_len..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        let scale = match unit {
+            "ns" => 1.0,
+            "us" | "µs" | "μs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            "" => {
+                return Err(anyhow::anyhow!(
+                    "Invalid duration `{s}`, missing unit after `{number}` (ns, us, ms, s, m, h)"
+                ))
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid duration `{s}`, unknown unit `{unit}` (ns, us, ms, s, m, h)"
+                ))
+            }
+        };
+        nanos += value * scale;
+        rest = &rest[unit_len..];
+    }
+
+    Ok(Duration::from_nanos(nanos.round() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        for (text, expected) in [
+            ("0", Duration::ZERO),
+            ("60s", Duration::from_secs(60)),
+            ("1h30m", Duration::from_secs(5400)),
+            ("1.5s", Duration::from_millis(1500)),
+            ("300ms", Duration::from_millis(300)),
+            ("2h45m10.5s", Duration::from_millis(9910500)),
+            ("10us", Duration::from_micros(10)),
+            ("+5m", Duration::from_secs(300)),
+        ] {
+            assert_eq!(expected, parse(text).unwrap(), "{text}");
+        }
+
+        for text in ["", "60", "1d", "-1s", "s", "1h.m", "1..5s"] {
+            assert!(parse(text).is_err(), "{text}");
+        }
+        assert_eq!(
+            "Invalid duration `1d`, unknown unit `d` (ns, us, ms, s, m, h)",
+            parse("1d").unwrap_err().to_string()
+        );
+    }
+}
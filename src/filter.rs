@@ -0,0 +1,612 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{Args, ValueEnum};
+use regex::Regex;
+
+use crate::datetime;
+use crate::duration;
+use crate::models::{Job, Record};
+
+/// Matches names against a glob like `report-*`, or a regex between slashes like `/^report-\d+$/`.
+#[derive(Clone, Debug)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Like `from_str`, but plain text matches anywhere instead of being a glob.
+    pub fn substring(s: &str) -> anyhow::Result<Self> {
+        if s.len() > 2 && s.starts_with('/') && s.ends_with('/') {
+            return s.parse();
+        }
+
+        Ok(Pattern(Regex::new(&regex::escape(s))?))
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(re) = s
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+            .filter(|re| !re.is_empty())
+        {
+            return Regex::new(re)
+                .map(Pattern)
+                .map_err(|err| anyhow::anyhow!("Invalid regex `{re}`: {err}"));
+        }
+
+        let mut re = String::from("^");
+        for c in s.chars() {
+            match c {
+                '*' => re.push_str(".*"),
+                '?' => re.push('.'),
+                _ => re.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        re.push('$');
+
+        Ok(Pattern(Regex::new(&re)?))
+    }
+}
+
+/// A point in time, either a Go duration from now like `1h` (`-1h` for an hour ago),
+/// or a local `YYYY-MM-DD HH:MM:SS`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Moment {
+    FromNow(Duration),
+    Ago(Duration),
+    At(DateTime<Utc>),
+}
+
+impl Moment {
+    pub fn resolve(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let offset = |d: &Duration| chrono::Duration::from_std(*d).unwrap_or_default();
+        match self {
+            Moment::FromNow(d) => now + offset(d),
+            Moment::Ago(d) => now - offset(d),
+            Moment::At(t) => *t,
+        }
+    }
+}
+
+impl FromStr for Moment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+            if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+                return Local
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .map(|t| Moment::At(t.with_timezone(&Utc)))
+                    .ok_or_else(|| anyhow::anyhow!("Time `{s}` does not exist in local time"));
+            }
+        }
+
+        let parsed = match s.strip_prefix('-') {
+            Some(ago) => duration::parse(ago).map(Moment::Ago),
+            None => duration::parse(s).map(Moment::FromNow),
+        };
+        parsed.map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid time `{s}`, expected a duration like `1h` or `YYYY-MM-DD HH:MM:SS`"
+            )
+        })
+    }
+}
+
+/// Filters on the fields of jobs, all set ones must match.
+#[derive(Args, Clone, Debug, Default)]
+pub struct JobFilter {
+    /// Only jobs with this status, like running or paused
+    #[arg(long)]
+    pub status: Option<String>,
+    /// Only jobs of this type: datetime, interval or cron
+    #[arg(long = "type", value_name = "TYPE")]
+    pub job_type: Option<String>,
+    /// Only jobs running this func
+    #[arg(long)]
+    pub func_name: Option<String>,
+    /// Only jobs sent to this queue
+    #[arg(long)]
+    pub queue: Option<String>,
+    /// Only jobs whose name matches a glob like `report-*`, or a regex like `/^report-\d+$/`
+    #[arg(long, value_name = "PATTERN")]
+    pub name: Option<Pattern>,
+    /// Only jobs next running after this time, a duration from now like `10m` or `YYYY-MM-DD HH:MM:SS`
+    #[arg(long, value_name = "TIME")]
+    pub next_run_after: Option<Moment>,
+    /// Only jobs next running before this time, a duration from now like `1h` or `YYYY-MM-DD HH:MM:SS`
+    #[arg(long, value_name = "TIME")]
+    pub next_run_before: Option<Moment>,
+}
+
+impl JobFilter {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.job_type.is_none()
+            && self.func_name.is_none()
+            && self.queue.is_none()
+            && self.name.is_none()
+            && self.next_run_after.is_none()
+            && self.next_run_before.is_none()
+    }
+
+    pub fn matches(&self, job: &Job, now: DateTime<Utc>) -> bool {
+        let differs = |filter: &Option<String>, value: &str| {
+            filter
+                .as_ref()
+                .is_some_and(|f| !f.eq_ignore_ascii_case(value))
+        };
+        if differs(&self.status, &job.status)
+            || differs(&self.job_type, &job.job_type)
+            || self.func_name.as_ref().is_some_and(|f| f != &job.func_name)
+            || self.queue.as_ref().is_some_and(|q| !job.queues.contains(q))
+            || self.name.as_ref().is_some_and(|p| !p.is_match(&job.name))
+        {
+            return false;
+        }
+
+        if self.next_run_after.is_some() || self.next_run_before.is_some() {
+            let Some(next_run) = datetime::parse_utc(job.next_run_time.as_deref()) else {
+                return false;
+            };
+            if self
+                .next_run_after
+                .is_some_and(|m| next_run < m.resolve(now))
+                || self
+                    .next_run_before
+                    .is_some_and(|m| next_run > m.resolve(now))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn apply(&self, jobs: Vec<Job>, now: DateTime<Utc>) -> Vec<Job> {
+        jobs.into_iter().filter(|j| self.matches(j, now)).collect()
+    }
+}
+
+/// Filters then optionally sorts jobs.
+pub fn select_jobs(
+    jobs: Vec<Job>,
+    filter: &JobFilter,
+    sort: Option<JobSort>,
+    now: DateTime<Utc>,
+) -> Vec<Job> {
+    let mut jobs = filter.apply(jobs, now);
+    if let Some(by) = sort {
+        sort_jobs(&mut jobs, by);
+    }
+
+    jobs
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum JobSort {
+    Name,
+    NextRun,
+    LastRun,
+    Status,
+}
+
+/// Sorts jobs in place, jobs without a time go last.
+pub fn sort_jobs(jobs: &mut [Job], by: JobSort) {
+    let by_time =
+        |a: Option<&str>, b: Option<&str>| match (datetime::parse_utc(a), datetime::parse_utc(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+
+    jobs.sort_by(|a, b| match by {
+        JobSort::Name => a.name.cmp(&b.name),
+        JobSort::NextRun => by_time(a.next_run_time.as_deref(), b.next_run_time.as_deref()),
+        JobSort::LastRun => by_time(a.last_run_time.as_deref(), b.last_run_time.as_deref()),
+        JobSort::Status => a.status.cmp(&b.status).then_with(|| a.name.cmp(&b.name)),
+    });
+}
+
+fn query_pairs(text: &str) -> anyhow::Result<Vec<(&str, &str)>> {
+    text.split_whitespace()
+        .map(|pair| {
+            pair.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected `key=value`, found `{pair}`"))
+        })
+        .collect()
+}
+
+/// Parses the interactive `key=value` form of the list options, like `status=paused sort=name`.
+pub fn parse_job_query(text: &str) -> anyhow::Result<(JobFilter, Option<JobSort>)> {
+    let mut filter = JobFilter::default();
+    let mut sort = None;
+
+    for (key, value) in query_pairs(text)? {
+        match key {
+            "status" => filter.status = Some(value.to_string()),
+            "type" => filter.job_type = Some(value.to_string()),
+            "func_name" => filter.func_name = Some(value.to_string()),
+            "queue" => filter.queue = Some(value.to_string()),
+            "name" => filter.name = Some(value.parse()?),
+            "next_run_after" => filter.next_run_after = Some(value.parse()?),
+            "next_run_before" => filter.next_run_before = Some(value.parse()?),
+            "sort" => {
+                sort = Some(
+                    JobSort::from_str(&value.replace('_', "-"), true)
+                        .map_err(|_| anyhow::anyhow!("Unknown sort `{value}` (name, next_run, last_run, status)"))?,
+                )
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unknown key `{key}` (status, type, func_name, queue, name, next_run_after, next_run_before, sort)"
+                ))
+            }
+        }
+    }
+
+    Ok((filter, sort))
+}
+
+/// Statuses of records reported by the scheduler.
+const RECORD_STATUSES: [&str; 4] = ["completed", "error", "timeout", "running"];
+
+fn record_status_parser() -> impl TypedValueParser<Value = String> {
+    PossibleValuesParser::new(RECORD_STATUSES.map(|s| match s {
+        "completed" => PossibleValue::new(s).alias("success"),
+        _ => PossibleValue::new(s),
+    }))
+    .map(|s| match s.as_str() {
+        "success" => "completed".to_string(),
+        _ => s,
+    })
+}
+
+/// Parses a record status, mapping `success` to the `completed` of the scheduler.
+fn parse_record_status(text: &str) -> anyhow::Result<String> {
+    let status = text.to_lowercase();
+    if status == "success" {
+        return Ok("completed".to_string());
+    }
+    if !RECORD_STATUSES.contains(&status.as_str()) {
+        return Err(anyhow::anyhow!(
+            "Unknown status `{text}` ({})",
+            RECORD_STATUSES.join(", ")
+        ));
+    }
+
+    Ok(status)
+}
+
+/// Filters on the fields of records, all set ones must match.
+#[derive(Args, Clone, Debug, Default)]
+pub struct RecordFilter {
+    /// Only records with this status, `success` is an alias of completed
+    #[arg(long, value_parser = record_status_parser())]
+    pub status: Option<String>,
+    /// Only records started at or after this time, like `-12h` or `YYYY-MM-DD HH:MM:SS`
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub since: Option<Moment>,
+    /// Only records started before this time, like `-1h` or `YYYY-MM-DD HH:MM:SS`
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub until: Option<Moment>,
+    /// Only records that ran at least this long, like `5s`
+    #[arg(long, value_name = "DURATION", value_parser = duration::parse)]
+    pub min_duration: Option<Duration>,
+    /// Only records that ran at most this long, like `500ms`
+    #[arg(long, value_name = "DURATION", value_parser = duration::parse)]
+    pub max_duration: Option<Duration>,
+    /// Only records whose result contains this text, or matches a regex like `/time(d)? ?out/`
+    #[arg(long, value_name = "TEXT", value_parser = Pattern::substring)]
+    pub result: Option<Pattern>,
+}
+
+impl RecordFilter {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.min_duration.is_none()
+            && self.max_duration.is_none()
+            && self.result.is_none()
+    }
+
+    pub fn matches(&self, record: &Record, now: DateTime<Utc>) -> bool {
+        if self
+            .status
+            .as_ref()
+            .is_some_and(|s| !s.eq_ignore_ascii_case(&record.status))
+            || self
+                .result
+                .as_ref()
+                .is_some_and(|p| !p.is_match(&record.result))
+        {
+            return false;
+        }
+
+        if self.since.is_none()
+            && self.until.is_none()
+            && self.min_duration.is_none()
+            && self.max_duration.is_none()
+        {
+            return true;
+        }
+        let Some(start) = datetime::parse_utc(record.start_at.as_deref()) else {
+            return false;
+        };
+        if self.since.is_some_and(|m| start < m.resolve(now))
+            || self.until.is_some_and(|m| start >= m.resolve(now))
+        {
+            return false;
+        }
+
+        // Running records have no end yet, they have been running until now.
+        let end = match record.status.as_str() {
+            "running" => Some(now),
+            _ => datetime::parse_utc(record.end_at.as_deref()),
+        };
+        let Some(elapsed) = end.and_then(|end| (end - start).to_std().ok()) else {
+            return self.min_duration.is_none() && self.max_duration.is_none();
+        };
+
+        !(self.min_duration.is_some_and(|d| elapsed < d)
+            || self.max_duration.is_some_and(|d| elapsed > d))
+    }
+
+    pub fn apply(&self, records: Vec<Record>, now: DateTime<Utc>) -> Vec<Record> {
+        records
+            .into_iter()
+            .filter(|r| self.matches(r, now))
+            .collect()
+    }
+}
+
+/// Parses the interactive `key=value` form of the record filters, like `status=timeout since=-12h`.
+pub fn parse_record_query(text: &str) -> anyhow::Result<RecordFilter> {
+    let mut filter = RecordFilter::default();
+
+    for (key, value) in query_pairs(text)? {
+        match key {
+            "status" => filter.status = Some(parse_record_status(value)?),
+            "since" => filter.since = Some(value.parse()?),
+            "until" => filter.until = Some(value.parse()?),
+            "min_duration" => filter.min_duration = Some(duration::parse(value)?),
+            "max_duration" => filter.max_duration = Some(duration::parse(value)?),
+            "result" => filter.result = Some(Pattern::substring(value)?),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unknown key `{key}` (status, since, until, min_duration, max_duration, result)"
+                ))
+            }
+        }
+    }
+
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn jobs() -> Vec<Job> {
+        serde_json::from_value(json!([
+            {
+                "id": "1", "name": "report-1", "type": "cron", "func_name": "a.Report",
+                "queues": ["default"], "status": "running",
+                "next_run_time": "2024-04-16T08:30:00Z", "last_run_time": "2024-04-16T07:30:00Z"
+            },
+            {
+                "id": "2", "name": "cleanup", "type": "interval", "func_name": "a.Cleanup",
+                "queues": ["slow"], "status": "paused",
+                "next_run_time": "2024-04-16T12:00:00Z", "last_run_time": "0001-01-01T00:00:00Z"
+            },
+            {
+                "id": "3", "name": "report-22", "type": "datetime", "func_name": "a.Report",
+                "queues": ["default", "slow"], "status": "running",
+                "next_run_time": null, "last_run_time": "2024-04-16T06:00:00Z"
+            },
+        ]))
+        .unwrap()
+    }
+
+    fn ids(jobs: &[Job]) -> Vec<&str> {
+        jobs.iter().map(|j| j.id.as_str()).collect()
+    }
+
+    #[test]
+    fn it_pattern() {
+        let glob: Pattern = "report-*".parse().unwrap();
+        assert!(glob.is_match("report-1"));
+        assert!(!glob.is_match("my-report-1"));
+        let glob: Pattern = "job.?".parse().unwrap();
+        assert!(glob.is_match("job.1"));
+        assert!(!glob.is_match("jobx1"));
+
+        let re: Pattern = r"/^report-\d$/".parse().unwrap();
+        assert!(re.is_match("report-1"));
+        assert!(!re.is_match("report-22"));
+        assert!("/(/".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn it_moment() {
+        let now = Utc.with_ymd_and_hms(2024, 4, 16, 8, 0, 0).unwrap();
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 4, 16, 9, 30, 0).unwrap(),
+            "1h30m".parse::<Moment>().unwrap().resolve(now)
+        );
+        let naive =
+            NaiveDateTime::parse_from_str("2024-04-17 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            Local
+                .from_local_datetime(&naive)
+                .unwrap()
+                .with_timezone(&Utc),
+            "2024-04-17T10:00:00"
+                .parse::<Moment>()
+                .unwrap()
+                .resolve(now)
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 4, 16, 6, 0, 0).unwrap(),
+            "-2h".parse::<Moment>().unwrap().resolve(now)
+        );
+        assert!("tomorrow".parse::<Moment>().is_err());
+    }
+
+    #[test]
+    fn it_filter() {
+        let now = Utc.with_ymd_and_hms(2024, 4, 16, 8, 0, 0).unwrap();
+        assert!(JobFilter::default().is_empty());
+        assert_eq!(
+            vec!["1", "2", "3"],
+            ids(&JobFilter::default().apply(jobs(), now))
+        );
+
+        for (filter, expected) in [
+            (
+                JobFilter {
+                    status: Some("Running".to_string()),
+                    ..Default::default()
+                },
+                vec!["1", "3"],
+            ),
+            (
+                JobFilter {
+                    job_type: Some("interval".to_string()),
+                    ..Default::default()
+                },
+                vec!["2"],
+            ),
+            (
+                JobFilter {
+                    func_name: Some("a.Report".to_string()),
+                    queue: Some("slow".to_string()),
+                    ..Default::default()
+                },
+                vec!["3"],
+            ),
+            (
+                JobFilter {
+                    name: Some("report-*".parse().unwrap()),
+                    ..Default::default()
+                },
+                vec!["1", "3"],
+            ),
+            (
+                JobFilter {
+                    next_run_before: Some("1h".parse().unwrap()),
+                    ..Default::default()
+                },
+                vec!["1"],
+            ),
+            (
+                JobFilter {
+                    next_run_after: Some("1h".parse().unwrap()),
+                    ..Default::default()
+                },
+                vec!["2"],
+            ),
+        ] {
+            assert!(!filter.is_empty());
+            assert_eq!(expected, ids(&filter.apply(jobs(), now)), "{filter:?}");
+        }
+    }
+
+    #[test]
+    fn it_record_filter() {
+        let now = Utc.with_ymd_and_hms(2024, 4, 16, 8, 0, 0).unwrap();
+        let records: Vec<Record> = serde_json::from_value(json!([
+            {
+                "id": 1, "status": "completed", "result": "ok",
+                "start_at": "2024-04-16T01:00:00Z", "end_at": "2024-04-16T01:00:02Z"
+            },
+            {
+                "id": 2, "status": "timeout", "result": "context deadline exceeded",
+                "start_at": "2024-04-16T02:00:00Z", "end_at": "2024-04-16T02:01:00Z"
+            },
+            {
+                "id": 3, "status": "error", "result": "error: Timed out",
+                "start_at": "2024-04-16T07:00:00Z", "end_at": "2024-04-16T07:00:00.5Z"
+            },
+            {"id": 4, "status": "running", "result": "", "start_at": "2024-04-16T07:30:00Z"},
+        ]))
+        .unwrap();
+        let ids = |filter: &RecordFilter| -> Vec<u64> {
+            filter
+                .apply(records.clone(), now)
+                .iter()
+                .map(|r| r.id)
+                .collect()
+        };
+
+        assert!(RecordFilter::default().is_empty());
+        assert_eq!(vec![1, 2, 3, 4], ids(&RecordFilter::default()));
+        for (query, expected) in [
+            ("status=timeout", vec![2]),
+            ("status=completed", vec![1]),
+            ("status=success", vec![1]),
+            ("since=-2h", vec![3, 4]),
+            ("since=-12h until=-4h", vec![1, 2]),
+            ("min_duration=30s", vec![2, 4]),
+            ("max_duration=1s", vec![3]),
+            ("result=deadline", vec![2]),
+            ("result=/(?i)timed?.out/", vec![3]),
+        ] {
+            let filter = parse_record_query(query).unwrap();
+            assert!(!filter.is_empty());
+            assert_eq!(expected, ids(&filter), "{query}");
+        }
+        assert!(parse_record_query("job=x").is_err());
+        assert!(parse_record_query("status=done").is_err());
+        assert!(parse_record_query("min_duration=5").is_err());
+    }
+
+    #[test]
+    fn it_sort_jobs() {
+        for (by, expected) in [
+            (JobSort::Name, vec!["2", "1", "3"]),
+            (JobSort::NextRun, vec!["1", "2", "3"]),
+            (JobSort::LastRun, vec!["3", "1", "2"]),
+            (JobSort::Status, vec!["2", "1", "3"]),
+        ] {
+            let mut jobs = jobs();
+            sort_jobs(&mut jobs, by);
+            assert_eq!(expected, ids(&jobs), "{by:?}");
+        }
+    }
+
+    #[test]
+    fn it_parse_job_query() {
+        let (filter, sort) = parse_job_query("status=paused name=/^c/ sort=next_run").unwrap();
+        assert_eq!(Some("paused".to_string()), filter.status);
+        assert!(filter.name.unwrap().is_match("cleanup"));
+        assert_eq!(Some(JobSort::NextRun), sort);
+
+        let (filter, sort) = parse_job_query("  ").unwrap();
+        assert!(filter.is_empty());
+        assert_eq!(None, sort);
+
+        assert_eq!(
+            "Expected `key=value`, found `paused`",
+            parse_job_query("paused").unwrap_err().to_string()
+        );
+        assert!(parse_job_query("color=red").is_err());
+        assert!(parse_job_query("sort=size").is_err());
+    }
+}
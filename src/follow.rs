@@ -0,0 +1,224 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::api_client::AGScheduler;
+use crate::filter::RecordFilter;
+use crate::models::Record;
+use crate::output::{self, OutputFormat, RecordStream};
+
+/// Pages walked at most by one poll, older records are skipped beyond them.
+const MAX_PAGES: u64 = 20;
+
+/// Remembers the statuses of the last poll, to tell which records are new or finished since.
+#[derive(Debug, Default)]
+pub struct Follower {
+    statuses: HashMap<u64, String>,
+}
+
+impl Follower {
+    /// Whether the records of the pages walked so far, newest first, may miss some that changed:
+    /// none of them was seen by the last poll, or a record running then is older than all of them.
+    pub fn needs_more(&self, records: &[Record]) -> bool {
+        if self.statuses.is_empty() {
+            return false;
+        }
+        let Some(oldest) = records.iter().map(|r| r.id).min() else {
+            return false;
+        };
+
+        !records.iter().any(|r| self.statuses.contains_key(&r.id))
+            || self
+                .statuses
+                .iter()
+                .any(|(id, status)| status == "running" && *id < oldest)
+    }
+
+    /// Returns the records that are new or no longer running, oldest first.
+    pub fn update(&mut self, records: Vec<Record>) -> Vec<Record> {
+        let mut fresh: Vec<Record> = records
+            .iter()
+            .filter(|r| match self.statuses.get(&r.id) {
+                Some(status) => status == "running" && r.status != "running",
+                None => true,
+            })
+            .cloned()
+            .collect();
+        fresh.sort_by_key(|r| r.id);
+
+        self.statuses = records.into_iter().map(|r| (r.id, r.status)).collect();
+
+        fresh
+    }
+}
+
+/// Fetches the latest pages of records, walking back until `follower` has seen everything older.
+async fn poll(
+    ags: &AGScheduler,
+    job_id: &str,
+    page_size: u64,
+    follower: &Follower,
+) -> anyhow::Result<Vec<Record>> {
+    let mut records: Vec<Record> = vec![];
+    let mut ids = HashSet::new();
+    for page in 1..=MAX_PAGES {
+        let res = ags.list_records(job_id, page, page_size).await?.res;
+        let full = res.len() as u64 >= page_size;
+        // Records shift to the next page when new ones arrive in between.
+        records.extend(res.into_iter().filter(|r| ids.insert(r.id)));
+        if !full || !follower.needs_more(&records) {
+            return Ok(records);
+        }
+    }
+
+    output::show_warnings(&[format!(
+        "More than {MAX_PAGES} pages of records changed since the last poll, older ones are skipped"
+    )]);
+    Ok(records)
+}
+
+/// Polls the latest records every `interval` until Ctrl-C, printing only what changed
+/// and matches `filter`.
+///
+/// Errors of a single poll are printed to stderr and do not stop following.
+pub async fn follow(
+    ags: &AGScheduler,
+    job_id: &str,
+    filter: &RecordFilter,
+    page_size: u64,
+    interval: Duration,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Table && format != OutputFormat::Ndjson {
+        return Err(anyhow::anyhow!(
+            "`--follow` needs the table or ndjson output"
+        ));
+    }
+
+    let mut follower = Follower::default();
+    let mut stream = RecordStream::new(format);
+    let result = loop {
+        tokio::select! {
+            result = poll(ags, job_id, page_size, &follower) => match result {
+                Ok(records) => stream.push(filter.apply(follower.update(records), Utc::now()))?,
+                Err(err) => eprintln!("Error: {err}"),
+            },
+            result = tokio::signal::ctrl_c() => break result,
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            result = tokio::signal::ctrl_c() => break result,
+        }
+    };
+
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn records(value: serde_json::Value) -> Vec<Record> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ids(records: &[Record]) -> Vec<u64> {
+        records.iter().map(|r| r.id).collect()
+    }
+
+    #[test]
+    fn it_update() {
+        let mut follower = Follower::default();
+
+        let first = records(json!([
+            {"id": 2, "status": "running"},
+            {"id": 1, "status": "success"},
+        ]));
+        assert_eq!(vec![1, 2], ids(&follower.update(first)));
+
+        let second = records(json!([
+            {"id": 3, "status": "running"},
+            {"id": 2, "status": "running"},
+            {"id": 1, "status": "success"},
+        ]));
+        assert_eq!(vec![3], ids(&follower.update(second)));
+
+        let third = records(json!([
+            {"id": 3, "status": "timeout"},
+            {"id": 2, "status": "success"},
+        ]));
+        let fresh = follower.update(third);
+        assert_eq!(vec![2, 3], ids(&fresh));
+        assert_eq!("timeout", fresh[1].status);
+
+        let fourth = records(json!([
+            {"id": 3, "status": "timeout"},
+            {"id": 2, "status": "success"},
+        ]));
+        assert!(follower.update(fourth).is_empty());
+    }
+
+    #[test]
+    fn it_needs_more() {
+        let mut follower = Follower::default();
+        assert!(!follower.needs_more(&records(json!([{"id": 5}]))));
+
+        follower.update(records(json!([
+            {"id": 3, "status": "success"},
+            {"id": 2, "status": "running"},
+            {"id": 1, "status": "success"},
+        ])));
+        assert!(follower.needs_more(&records(json!([{"id": 5}, {"id": 4}]))));
+        assert!(follower.needs_more(&records(json!([{"id": 4}, {"id": 3}]))));
+        assert!(!follower.needs_more(&records(json!([{"id": 3}, {"id": 2}]))));
+    }
+
+    #[tokio::test]
+    async fn it_poll() {
+        let mut server = mockito::Server::new_async().await;
+        for (page, res) in [
+            (1, json!([{"id": 5}, {"id": 4}])),
+            (2, json!([{"id": 4}, {"id": 3}])),
+        ] {
+            server
+                .mock("GET", format!("/recorder/records?page={page}&page_size=2").as_str())
+                .with_body(
+                    json!({"data": {"page": page, "page_size": 2, "total": 5, "res": res}, "error": ""})
+                        .to_string(),
+                )
+                .expect(1)
+                .create_async()
+                .await;
+        }
+        let ags = AGScheduler::new(crate::http::Config::new(&server.url())).unwrap();
+
+        let mut follower = Follower::default();
+        follower.update(records(json!([{"id": 3, "status": "success"}])));
+        let polled = poll(&ags, "", 2, &follower).await.unwrap();
+        assert_eq!(vec![5, 4, 3], ids(&polled));
+        assert_eq!(vec![4, 5], ids(&follower.update(polled)));
+    }
+
+    #[tokio::test]
+    async fn it_follow_format() {
+        let ags = AGScheduler::new(crate::http::Config::new("http://127.0.0.1:1")).unwrap();
+        let err = follow(
+            &ags,
+            "",
+            &RecordFilter::default(),
+            10,
+            Duration::from_secs(1),
+            OutputFormat::Json,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            "`--follow` needs the table or ndjson output",
+            err.to_string()
+        );
+    }
+}
@@ -1,17 +1,98 @@
 use std::default::Default;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Method;
+use serde::Serialize;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 
-use crate::utils;
+/// Connection settings of one AGScheduler endpoint, passed into every request.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub endpoint: String,
+    pub password_sha2: String,
+    pub timeout: Duration,
+    pub headers: Vec<(String, String)>,
+    /// Speak HTTP/2 without negotiating it first, HTTP/1.1 is used otherwise.
+    pub http2: bool,
+    /// Accept any TLS certificate, only meant for testing setups.
+    pub insecure: bool,
+    /// Extra PEM root certificate trusted for TLS.
+    pub ca_cert: Option<PathBuf>,
+    /// Keep the requests that change something from being sent, see `DryRun`.
+    pub dry_run: bool,
+}
 
-pub static mut PASSWORD_SHA2: String = String::new();
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            endpoint: String::from("http://127.0.0.1:36370"),
+            password_sha2: String::new(),
+            timeout: Duration::from_secs(6),
+            headers: vec![],
+            http2: false,
+            insecure: false,
+            ca_cert: None,
+            dry_run: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn new(endpoint: &str) -> Self {
+        Config {
+            endpoint: endpoint.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the SHA256 of the password sent in `Auth-Password-SHA2`, an empty password sends none.
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.password_sha2 = String::new();
+        if !password.is_empty() {
+            self.password_sha2 = hex::encode(Sha256::digest(password));
+        }
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_http2(mut self, http2: bool) -> Self {
+        self.http2 = http2;
+        self
+    }
+
+    pub fn with_insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
+    pub fn with_ca_cert(mut self, ca_cert: Option<PathBuf>) -> Self {
+        self.ca_cert = ca_cert;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
 
 pub struct Options {
     pub method: Method,
     pub body: String,
-    pub timeout: Duration,
 }
 
 impl Default for Options {
@@ -19,57 +100,123 @@ impl Default for Options {
         Options {
             method: Method::GET,
             body: String::new(),
-            timeout: Duration::from_secs(6),
         }
     }
 }
 
-pub async fn fetch(url: String, options: Options) -> anyhow::Result<Value> {
-    let client = reqwest::Client::new();
-
-    let password_sha2;
-    unsafe { password_sha2 = PASSWORD_SHA2.as_str().to_string() }
+/// A request that was not sent because of `Config::dry_run`, returned as the error of `Client::fetch`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DryRun {
+    pub method: String,
+    pub url: String,
+    /// The JSON body, `null` if there is none.
+    pub body: Value,
+}
 
-    let response = client
-        .request(options.method, url)
-        .header("Auth-Password-SHA2", password_sha2)
-        .body(options.body)
-        .timeout(options.timeout)
-        .send()
-        .await?;
-    if !response.status().is_success() {
-        let err = response.text().await?;
-        return Err(anyhow::anyhow!(err));
+impl DryRun {
+    fn new(method: &Method, url: &str, body: &str) -> Self {
+        DryRun {
+            method: method.to_string(),
+            url: url.to_string(),
+            body: match serde_json::from_str(body) {
+                Ok(value) => value,
+                Err(_) if body.is_empty() => Value::Null,
+                Err(_) => Value::String(body.to_string()),
+            },
+        }
     }
+}
+
+/// The method and URL, followed by the pretty-printed body if any.
+impl fmt::Display for DryRun {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dry run, not sent: {} {}", self.method, self.url)?;
+        if !self.body.is_null() {
+            let body = serde_json::to_string_pretty(&self.body).map_err(|_| fmt::Error)?;
+            write!(f, "\n{body}")?;
+        }
 
-    let body = response.text().await?;
-    let v: Value = serde_json::from_str(&body)?;
-    if v["error"] != "" {
-        return Err(anyhow::anyhow!(v["error"].as_str().unwrap().to_string()));
+        Ok(())
     }
+}
+
+impl std::error::Error for DryRun {}
 
-    Ok(v["data"].to_owned())
+/// Long-lived connection to one endpoint, reusing pooled keep-alive connections across requests.
+#[derive(Clone, Debug)]
+pub struct Client {
+    pub config: Config,
+    inner: reqwest::Client,
 }
 
-pub async fn fetch_show_json(url: String, options: Options) {
-    match fetch(url, options).await {
-        Ok(result) => {
-            utils::show_json(result);
+impl Client {
+    pub fn new(config: Config) -> anyhow::Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Auth-Password-SHA2",
+            HeaderValue::from_str(&config.password_sha2)?,
+        );
+        for (name, value) in &config.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
         }
-        Err(err) => {
-            println!("Error: {}", err)
+
+        let mut builder = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(config.timeout)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .tcp_keepalive(Duration::from_secs(60))
+            .gzip(true)
+            .danger_accept_invalid_certs(config.insecure);
+        if let Some(path) = &config.ca_cert {
+            let pem = fs::read(path)
+                .map_err(|err| anyhow::anyhow!("Read `{}`: {err}", path.display()))?;
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+        }
+        if config.http2 {
+            builder = builder.http2_prior_knowledge();
+        } else {
+            builder = builder.http1_only();
         }
+
+        Ok(Client {
+            inner: builder.build()?,
+            config,
+        })
     }
-}
 
-pub async fn fetch_show_ok(url: String, options: Options) {
-    match fetch(url, options).await {
-        Ok(_) => {
-            println!("Ok")
+    /// Sends a request and returns the `data` of the response.
+    ///
+    /// In dry-run mode, requests other than GET are not sent and fail with a `DryRun` error instead.
+    pub async fn fetch(&self, url_path: &str, options: Options) -> anyhow::Result<Value> {
+        let url = format!("{}{}", self.config.endpoint, url_path);
+        if self.config.dry_run && options.method != Method::GET {
+            return Err(DryRun::new(&options.method, &url, &options.body).into());
+        }
+
+        let response = self
+            .inner
+            .request(options.method, url)
+            .body(options.body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let err = response.text().await?;
+            return Err(anyhow::anyhow!(err));
         }
-        Err(err) => {
-            println!("Error: {}", err)
+
+        let body = response.text().await?;
+        let v: Value = serde_json::from_str(&body)?;
+        match &v["error"] {
+            Value::Null => {}
+            Value::String(err) if err.is_empty() => {}
+            Value::String(err) => return Err(anyhow::anyhow!(err.to_string())),
+            err => return Err(anyhow::anyhow!(err.to_string())),
         }
+
+        Ok(v["data"].to_owned())
     }
 }
 
@@ -92,13 +239,78 @@ mod tests {
             .create_async()
             .await;
 
-        let result = fetch(format!("{}{}", url, "/hello"), Options::default())
+        let result = Client::new(Config::new(&url))
+            .unwrap()
+            .fetch("/hello", Options::default())
             .await
             .unwrap();
 
         assert_eq!("{\"hello\":\"world\"}", result.to_string());
     }
 
+    #[tokio::test]
+    async fn it_fetch_dry_run() {
+        let mut server = mockito::Server::new_async().await;
+        let get = server
+            .mock("GET", "/scheduler/job/x")
+            .with_body(json!({"data": {"id": "x"}, "error": ""}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let put = server
+            .mock("PUT", "/scheduler/job")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = Client::new(Config::new(&server.url()).with_dry_run(true)).unwrap();
+        assert_eq!(
+            json!({"id": "x"}),
+            client
+                .fetch("/scheduler/job/x", Options::default())
+                .await
+                .unwrap()
+        );
+        let options = Options {
+            method: Method::PUT,
+            body: json!({"id": "x", "name": "myJob"}).to_string(),
+        };
+        let err = client.fetch("/scheduler/job", options).await.unwrap_err();
+        assert_eq!(
+            Some(&DryRun {
+                method: "PUT".to_string(),
+                url: format!("{}/scheduler/job", server.url()),
+                body: json!({"id": "x", "name": "myJob"}),
+            }),
+            err.downcast_ref::<DryRun>()
+        );
+
+        get.assert_async().await;
+        put.assert_async().await;
+    }
+
+    #[test]
+    fn it_dry_run_display() {
+        assert_eq!(
+            "Dry run, not sent: DELETE http://127.0.0.1:36370/scheduler/job/x",
+            DryRun::new(
+                &Method::DELETE,
+                "http://127.0.0.1:36370/scheduler/job/x",
+                ""
+            )
+            .to_string()
+        );
+        assert_eq!(
+            "Dry run, not sent: POST http://127.0.0.1:36370/scheduler/job\n{\n  \"name\": \"myJob\"\n}",
+            DryRun::new(
+                &Method::POST,
+                "http://127.0.0.1:36370/scheduler/job",
+                r#"{"name": "myJob"}"#
+            )
+            .to_string()
+        );
+    }
+
     #[tokio::test]
     async fn it_fetch_status_failed() {
         let mut server = mockito::Server::new_async().await;
@@ -113,13 +325,40 @@ mod tests {
             .create_async()
             .await;
 
-        let result = fetch(format!("{}{}", url, "/"), Options::default())
+        let result = Client::new(Config::new(&url))
+            .unwrap()
+            .fetch("/", Options::default())
             .await
             .unwrap_err();
 
         assert_eq!(body, result.to_string());
     }
 
+    #[tokio::test]
+    async fn it_fetch_error_field() {
+        let mut server = mockito::Server::new_async().await;
+        let client = Client::new(Config::new(&server.url())).unwrap();
+
+        for (body, expected) in [
+            (json!({"data": 1, "error": null}), Ok(json!(1))),
+            (json!({"data": 2}), Ok(json!(2))),
+            (json!({"data": 3, "error": ""}), Ok(json!(3))),
+            (
+                json!({"data": null, "error": {"code": 7}}),
+                Err(r#"{"code":7}"#.to_string()),
+            ),
+        ] {
+            let mock = server
+                .mock("GET", "/error")
+                .with_body(body.to_string())
+                .create_async()
+                .await;
+            let result = client.fetch("/error", Options::default()).await;
+            assert_eq!(expected, result.map_err(|err| err.to_string()));
+            mock.remove_async().await;
+        }
+    }
+
     #[tokio::test]
     async fn it_fetch_error() {
         let mut server = mockito::Server::new_async().await;
@@ -134,16 +373,105 @@ mod tests {
             .create_async()
             .await;
 
-        let result = fetch(
-            format!("{}{}", url, "/job"),
-            Options {
-                method: Method::POST,
-                ..Default::default()
-            },
-        )
-        .await
-        .unwrap_err();
+        let result = Client::new(Config::new(&url))
+            .unwrap()
+            .fetch(
+                "/job",
+                Options {
+                    method: Method::POST,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
 
         assert_eq!("`id` not found!", result.to_string());
     }
+
+    #[tokio::test]
+    async fn it_fetch_configs() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let body = json!({"data": "ok", "error": ""}).to_string();
+
+        for password in ["password1", "password2"] {
+            server
+                .mock("GET", "/info")
+                .match_header(
+                    "Auth-Password-SHA2",
+                    hex::encode(Sha256::digest(password)).as_str(),
+                )
+                .match_header("X-Request-Source", "ci")
+                .with_status(200)
+                .with_body(&body)
+                .expect(1)
+                .create_async()
+                .await;
+        }
+
+        let config1 = Config::new(&url)
+            .with_password("password1")
+            .with_header("X-Request-Source", "ci");
+        let config2 = config1.clone().with_password("password2");
+        let client1 = Client::new(config1).unwrap();
+        let client2 = Client::new(config2).unwrap();
+        let (result1, result2) = tokio::join!(
+            client1.fetch("/info", Options::default()),
+            client2.fetch("/info", Options::default())
+        );
+
+        assert_eq!("ok", result1.unwrap());
+        assert_eq!("ok", result2.unwrap());
+    }
+
+    #[tokio::test]
+    async fn it_fetch_timeout() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        server
+            .mock("GET", "/slow")
+            .with_chunked_body(|_| {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(())
+            })
+            .create_async()
+            .await;
+
+        let config = Config::new(&url).with_timeout(Duration::from_millis(50));
+        let client = Client::new(config).unwrap();
+        assert!(client.fetch("/slow", Options::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_client_reuse() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server
+            .mock("GET", "/info")
+            .match_header(
+                "accept-encoding",
+                mockito::Matcher::Regex("gzip".to_string()),
+            )
+            .with_status(200)
+            .with_body(json!({"data": 1, "error": ""}).to_string())
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = Client::new(Config::new(&url)).unwrap();
+        for _ in 0..3 {
+            assert_eq!(1, client.fetch("/info", Options::default()).await.unwrap());
+        }
+        mock.assert_async().await;
+
+        assert!(Client::new(Config::new(&url).with_http2(true)).is_ok());
+        assert!(Client::new(Config::new(&url).with_header("Bad Name", "x")).is_err());
+        assert!(Client::new(Config::new(&url).with_insecure(true)).is_ok());
+        assert!(
+            Client::new(Config::new(&url).with_ca_cert(Some("/nonexistent.pem".into()))).is_err()
+        );
+    }
 }
@@ -1,15 +1,28 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
-use chrono::Local;
-use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use std::io::{self, IsTerminal};
+
+use chrono::{Local, Utc};
+use dialoguer::{theme::ColorfulTheme, Confirm, FuzzySelect, Input, MultiSelect, Select};
 use mockall::automock;
 
+use crate::cron::CronExpr;
+use crate::filter;
+use crate::models::Job;
+use crate::output;
+use crate::timezone;
+use crate::validate;
+
 #[automock]
 pub trait InteractionTrait {
     fn input_common(&self, prompt: &str) -> String;
     fn input_id(&self) -> String;
     fn input_job_id(&self) -> String;
+    fn input_job_query(&self) -> String;
+    fn input_record_query(&self) -> String;
     fn confirm_delete(&self) -> bool;
+    fn confirm_common(&self, prompt: &str) -> bool;
+    fn is_terminal(&self) -> bool;
 
     fn input_common_default(&self, prompt: &str, default: &str, text: &str) -> String;
     fn input_name(&self, text: &str) -> String;
@@ -25,12 +38,40 @@ pub trait InteractionTrait {
     fn input_page_size(&self, text: &str) -> String;
 
     fn select_common(&self, prompt: &str, selections: Vec<String>, default: usize) -> String;
-    fn select_type(&self) -> String;
-    fn select_func_name(&self, selections: Vec<String>) -> String;
+    fn select_type(&self, text: &str) -> String;
+    fn select_func_name(&self, selections: Vec<String>, text: &str) -> String;
+    fn select_job(&self, jobs: Vec<Job>) -> String;
+    fn select_fields(&self, fields: Vec<String>) -> Vec<String>;
 }
 
 pub struct Interaction;
 
+impl Interaction {
+    /// Like `input_common_default`, re-prompting with the error until `validate` accepts the trimmed input.
+    ///
+    /// The previous value, if any, becomes the default, so clearing the line keeps it.
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn input_validated<F>(&self, prompt: &str, default: &str, text: &str, mut validate: F) -> String
+    where
+        F: FnMut(&str) -> anyhow::Result<()>,
+    {
+        let mut default = default.to_string();
+        if !text.is_empty() {
+            default = text.to_string();
+        }
+
+        Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(default.clone())
+            .with_initial_text(default)
+            .validate_with(|input: &String| validate(input.trim()).map_err(|err| err.to_string()))
+            .interact_text()
+            .unwrap()
+            .trim()
+            .to_string()
+    }
+}
+
 impl InteractionTrait for Interaction {
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_common(&self, prompt: &str) -> String {
@@ -50,15 +91,58 @@ impl InteractionTrait for Interaction {
         self.input_common("JobId").trim().to_string()
     }
 
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn input_job_query(&self) -> String {
+        Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Filter (like `status=paused name=report-* sort=next_run`, empty for all)")
+            .allow_empty(true)
+            .validate_with(|input: &String| {
+                filter::parse_job_query(input)
+                    .map(|_| ())
+                    .map_err(|err| err.to_string())
+            })
+            .interact_text()
+            .unwrap()
+            .trim()
+            .to_string()
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn input_record_query(&self) -> String {
+        Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Filter (like `status=timeout since=-12h result=deadline`, empty for all)")
+            .allow_empty(true)
+            .validate_with(|input: &String| {
+                filter::parse_record_query(input)
+                    .map(|_| ())
+                    .map_err(|err| err.to_string())
+            })
+            .interact_text()
+            .unwrap()
+            .trim()
+            .to_string()
+    }
+
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn confirm_delete(&self) -> bool {
+        self.confirm_common("Do you really really want to delete?")
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn confirm_common(&self, prompt: &str) -> bool {
         Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you really really want to delete?")
+            .with_prompt(prompt)
             .default(false)
             .show_default(true)
             .wait_for_newline(true)
             .interact()
-            .unwrap()
+            .unwrap_or(false)
+    }
+
+    /// Whether prompts can be answered, i.e. stdin and stderr are both terminals.
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn is_terminal(&self) -> bool {
+        io::stdin().is_terminal() && io::stderr().is_terminal()
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
@@ -85,57 +169,69 @@ impl InteractionTrait for Interaction {
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_start_at(&self, text: &str) -> String {
-        let local_datetime = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        self.input_common_default("StartAt", &local_datetime, text)
-            .trim()
-            .to_string()
+        let local_datetime = Local::now().format(validate::START_AT_FORMAT).to_string();
+        self.input_validated("StartAt", &local_datetime, text, |input| {
+            validate::start_at(input).map(|_| ())
+        })
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_interval(&self, text: &str) -> String {
-        self.input_common_default("Interval", "60s", text)
-            .trim()
-            .to_string()
+        self.input_validated("Interval", "60s", text, validate::interval)
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_cron_expr(&self, text: &str) -> String {
-        self.input_common_default("CronExpr", "*/1 * * * *", text)
-            .trim()
-            .to_string()
+        self.input_validated("CronExpr", "*/1 * * * *", text, |input| {
+            CronExpr::parse(input).map(|_| ())
+        })
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_timezone(&self, text: &str) -> String {
-        self.input_common_default("Timezone", "UTC", text)
-            .trim()
-            .to_string()
+        if !text.is_empty() && timezone::parse(text).is_err() {
+            output::show_warnings(&[format!("Unknown timezone `{text}`, kept unless changed")]);
+        }
+        let local = iana_time_zone::get_timezone().unwrap_or_default();
+        let (zones, default) = timezone::choices(text, &local, Utc::now());
+        let items: Vec<String> = zones
+            .iter()
+            .map(|z| format!("{} ({})", z.name, z.offset))
+            .collect();
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Timezone")
+            .default(default)
+            .max_length(8)
+            .items(&items[..])
+            .interact()
+            .unwrap();
+
+        zones[selection].name.to_string()
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_args(&self, text: &str) -> String {
-        self.input_common_default("Args", "{}", text)
-            .trim()
-            .to_string()
+        self.input_validated("Args", "{}", text, |input| {
+            validate::args(input).map(|_| ())
+        })
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_timeout(&self, text: &str) -> String {
-        self.input_common_default("Timeout", "1h", text)
-            .trim()
-            .to_string()
+        self.input_validated("Timeout", "1h", text, validate::timeout)
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_queues(&self, text: &str) -> String {
-        self.input_common_default("Queues", "[]", text)
-            .trim()
-            .to_string()
+        self.input_validated("Queues", "[]", text, |input| {
+            validate::queues(input).map(|_| ())
+        })
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn input_page(&self, text: &str) -> String {
-        self.input_common_default("Page", "1", text)
+        self.input_common_default("Page (or `all`)", "1", text)
             .trim()
             .to_string()
     }
@@ -161,17 +257,54 @@ impl InteractionTrait for Interaction {
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
-    fn select_type(&self) -> String {
+    fn select_type(&self, text: &str) -> String {
         let selections = vec![
             "Datetime".to_string(),
             "Interval".to_string(),
             "Cron".to_string(),
         ];
-        self.select_common("Select Type", selections, 1)
+        let default = selections
+            .iter()
+            .position(|s| s.eq_ignore_ascii_case(text))
+            .unwrap_or(1);
+        self.select_common("Select Type", selections, default)
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn select_func_name(&self, selections: Vec<String>, text: &str) -> String {
+        let default = selections.iter().position(|s| s == text).unwrap_or(0);
+        self.select_common("Select FuncName", selections, default)
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn select_job(&self, jobs: Vec<Job>) -> String {
+        let mut items = vec!["<Enter an ID>".to_string()];
+        items.extend(output::job_choices(&jobs));
+
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select Job")
+            .default(1)
+            .max_length(8)
+            .items(&items[..])
+            .interact()
+            .unwrap();
+
+        match selection {
+            0 => self.input_id(),
+            _ => jobs[selection - 1].id.to_string(),
+        }
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
-    fn select_func_name(&self, selections: Vec<String>) -> String {
-        self.select_common("Select FuncName", selections, 0)
+    fn select_fields(&self, fields: Vec<String>) -> Vec<String> {
+        MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the fields to change (Space to toggle)")
+            .max_length(10)
+            .items(&fields[..])
+            .interact()
+            .unwrap()
+            .into_iter()
+            .map(|i| fields[i].to_string())
+            .collect()
     }
 }
@@ -1,6 +1,23 @@
 pub mod api_client;
+pub mod apply;
+pub mod bulk;
+pub mod bundle;
+pub mod cli;
+pub mod cron;
+pub mod dashboard;
+pub mod diff;
+pub mod duration;
+pub mod filter;
+pub mod follow;
 pub mod http;
 pub mod interaction;
+pub mod menu;
+pub mod models;
+pub mod output;
+pub mod profile;
+pub mod stats;
+pub mod timezone;
+pub mod validate;
+pub mod watch;
 
 mod datetime;
-mod utils;
@@ -1,49 +1,49 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
-use std::env;
+use std::process;
 
 use clap::Parser;
 use dialoguer::{theme::ColorfulTheme, Select};
-use sha2::{Digest, Sha256};
 
 use agscheduler_cli::api_client::AGScheduler;
-use agscheduler_cli::http;
+use agscheduler_cli::cli::{self, Cli, Command};
 use agscheduler_cli::interaction::Interaction;
+use agscheduler_cli::menu::Menu;
+use agscheduler_cli::profile::{ConfigFile, Profile};
 
-/// Command line interface for AGScheduler
-#[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
-struct Args {
-    /// AGScheduler HTTP endpoint
-    #[arg(short, long, default_value = "http://127.0.0.1:36370")]
-    endpoint: String,
-    /// AGScheduler password
-    /// You can also use the AGSCHEDULERCLI_AUTH environment variable to pass this password more safely
-    #[arg(short, long, default_value = "", verbatim_doc_comment)]
-    password: String,
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn exit_on_err<T>(result: anyhow::Result<T>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    })
 }
 
 #[tokio::main]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn main() {
-    let args = Args::parse();
+    let args = Cli::parse();
+
+    let path = exit_on_err(ConfigFile::default_path());
 
-    let mut auth = env::var("AGSCHEDULERCLI_AUTH").unwrap_or("".to_string());
-    if !args.password.is_empty() {
-        auth = args.password;
+    if let Some(Command::Config(command)) = &args.command {
+        let output = exit_on_err(args.output(&Profile::default()));
+        exit_on_err(cli::run_config(&args, command, &path, output));
+        return;
     }
-    if !auth.is_empty() {
-        unsafe {
-            let hash = Sha256::digest(auth);
-            http::PASSWORD_SHA2 = hex::encode(hash);
-        }
+
+    let profile = exit_on_err(ConfigFile::load(&path).and_then(|file| args.profile(&file)));
+    let output = exit_on_err(args.output(&profile));
+    let ags = exit_on_err(args.config(&profile).and_then(AGScheduler::new));
+
+    if let Some(command) = args.command {
+        exit_on_err(cli::run(&ags, command, output, &Interaction {}).await);
+        return;
     }
 
-    println!("Connecting to `{}`...", args.endpoint);
+    println!("Connecting to `{}`...", ags.client.config.endpoint);
 
-    let ags = AGScheduler {
-        endpoint: args.endpoint,
-    };
+    let menu = Menu { ags, output };
 
     loop {
         let selections = &[
@@ -67,6 +67,8 @@ async fn main() {
             "Get Funcs",
             "Get Queues",
             "Get Cluster Nodes",
+            "Dashboard",
+            "Bulk Job Operation",
         ];
 
         let interaction = Interaction {};
@@ -80,26 +82,28 @@ async fn main() {
             .unwrap();
 
         match selection {
-            0 => ags.add_job(&interaction).await,
-            1 => ags.get_job(&interaction).await,
-            2 => ags.get_all_jobs().await,
-            3 => ags.update_job(&interaction).await,
-            4 => ags.delete_job(&interaction).await,
-            5 => ags.delete_all_jobs(&interaction).await,
-            6 => ags.pause_or_resume_job("pause", &interaction).await,
-            7 => ags.pause_or_resume_job("resume", &interaction).await,
-            8 => ags.run_or_schedule_job("run", &interaction).await,
-            9 => ags.run_or_schedule_job("schedule", &interaction).await,
-            10 => ags.start_or_stop("start").await,
-            11 => ags.start_or_stop("stop").await,
-            12 => ags.get_records(&interaction).await,
-            13 => ags.get_all_records(&interaction).await,
-            14 => ags.delete_records(&interaction).await,
-            15 => ags.delete_all_records(&interaction).await,
-            16 => ags.get_info().await,
-            17 => ags.get_funcs().await,
-            18 => ags.get_queues().await,
-            19 => ags.get_cluster_nodes().await,
+            0 => menu.add_job(&interaction).await,
+            1 => menu.get_job(&interaction).await,
+            2 => menu.get_all_jobs(&interaction).await,
+            3 => menu.update_job(&interaction).await,
+            4 => menu.delete_job(&interaction).await,
+            5 => menu.delete_all_jobs(&interaction).await,
+            6 => menu.pause_or_resume_job("pause", &interaction).await,
+            7 => menu.pause_or_resume_job("resume", &interaction).await,
+            8 => menu.run_or_schedule_job("run", &interaction).await,
+            9 => menu.run_or_schedule_job("schedule", &interaction).await,
+            10 => menu.start_or_stop("start").await,
+            11 => menu.start_or_stop("stop").await,
+            12 => menu.get_records(&interaction).await,
+            13 => menu.get_all_records(&interaction).await,
+            14 => menu.delete_records(&interaction).await,
+            15 => menu.delete_all_records(&interaction).await,
+            16 => menu.get_info().await,
+            17 => menu.get_funcs().await,
+            18 => menu.get_queues().await,
+            19 => menu.get_cluster_nodes().await,
+            20 => menu.dashboard().await,
+            21 => menu.bulk_job_operation(&interaction).await,
             _ => panic!("Error"),
         };
     }
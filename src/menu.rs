@@ -0,0 +1,851 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::api_client::AGScheduler;
+use crate::bulk::{self, BulkAction};
+use crate::cli;
+use crate::cron;
+use crate::dashboard;
+use crate::diff;
+use crate::filter;
+use crate::http;
+use crate::interaction::InteractionTrait;
+use crate::models::Job;
+use crate::output::{self, OutputFormat};
+use crate::validate;
+
+/// Number of fire times previewed when editing a cron job.
+const FIRE_TIMES: usize = 5;
+/// Number of record pages fetched at the same time when listing all of them.
+const RECORDS_CONCURRENCY: usize = 4;
+/// Number of jobs processed at the same time by a bulk operation.
+const BULK_CONCURRENCY: usize = 4;
+/// Fields prompted when adding a job, and offered when updating one along with its schedule.
+const EDIT_FIELDS: [&str; 7] = [
+    "name",
+    "type",
+    "timezone",
+    "func_name",
+    "args",
+    "timeout",
+    "queues",
+];
+
+/// Interactive flows of the operation menu, prompting through `InteractionTrait`.
+pub struct Menu {
+    pub ags: AGScheduler,
+    pub output: OutputFormat,
+}
+
+/// Field holding the schedule of a job type.
+fn schedule_field(job_type: &str) -> Option<&'static str> {
+    match job_type {
+        "datetime" => Some("start_at"),
+        "interval" => Some("interval"),
+        "cron" => Some("cron_expr"),
+        _ => None,
+    }
+}
+
+/// Prints the error of an operation, or the request it would have sent with `--dry-run`.
+fn show_err(result: anyhow::Result<()>) {
+    if let Err(err) = result {
+        match err.downcast_ref::<http::DryRun>() {
+            Some(request) => println!("{request}"),
+            None => println!("Error: {}", err),
+        }
+    }
+}
+
+impl Menu {
+    /// Prompts for the given fields of the job, `type` also prompting for the schedule of the type.
+    async fn _edit_job(
+        &self,
+        job: &Job,
+        fields: &[String],
+        interaction: &dyn InteractionTrait,
+    ) -> anyhow::Result<Job> {
+        let edit = |field: &str| fields.iter().any(|f| f == field);
+        let mut job = job.clone();
+
+        if edit("name") {
+            job.name = interaction.input_name(&job.name);
+        }
+        if edit("type") {
+            let _type = interaction.select_type(&job.job_type).to_lowercase();
+            if _type != job.job_type {
+                job.start_at.clear();
+                job.interval.clear();
+                job.cron_expr.clear();
+                job.job_type = _type;
+            }
+        }
+
+        let schedule = schedule_field(&job.job_type);
+        let edit_schedule = edit("type") || schedule.is_some_and(edit);
+        match job.job_type.as_str() {
+            "datetime" if edit_schedule => {
+                job.start_at = interaction.input_start_at(&job.start_at);
+            }
+            "interval" if edit_schedule => {
+                job.interval = interaction.input_interval(&job.interval);
+            }
+            "cron" if edit_schedule => {
+                job.cron_expr = interaction.input_cron_expr(&job.cron_expr);
+            }
+            _ => {}
+        }
+
+        if edit("timezone") {
+            let mut tz = iana_time_zone::get_timezone()?;
+            if !job.timezone.is_empty() {
+                tz = job.timezone.to_string();
+            }
+            job.timezone = interaction.input_timezone(&tz);
+        }
+        if job.job_type == "cron" && (edit_schedule || edit("timezone")) {
+            let times = cron::next_fire_times(&job.cron_expr, &job.timezone, FIRE_TIMES)?;
+            println!("{}", output::format_fire_times(&times));
+        }
+
+        if edit("func_name") {
+            let fn_selections = self
+                .ags
+                .list_funcs()
+                .await?
+                .into_iter()
+                .map(|f| f.name)
+                .collect();
+            job.func_name = interaction.select_func_name(fn_selections, &job.func_name);
+        }
+
+        if edit("args") {
+            let args = interaction.input_args(&json!(job.args).to_string());
+            job.args = validate::args(&args).map_err(|err| anyhow::anyhow!("Args: {err}"))?;
+        }
+        if edit("timeout") {
+            job.timeout = interaction.input_timeout(&job.timeout);
+        }
+        if edit("queues") {
+            let queues = interaction.input_queues(&json!(job.queues).to_string());
+            job.queues =
+                validate::queues(&queues).map_err(|err| anyhow::anyhow!("Queues: {err}"))?;
+        }
+
+        validate::job(&job)?;
+        output::show_warnings(&validate::warnings(&job, Utc::now()));
+
+        Ok(job)
+    }
+
+    /// Picks a job from the list, falling back to typing its ID when there is nothing to pick.
+    async fn _select_job_id(&self, interaction: &dyn InteractionTrait) -> String {
+        match self.ags.list_jobs().await {
+            Ok(jobs) if !jobs.is_empty() => interaction.select_job(jobs),
+            _ => interaction.input_id(),
+        }
+    }
+
+    pub async fn add_job(&self, interaction: &dyn InteractionTrait) {
+        show_err(
+            async {
+                let fields = EDIT_FIELDS.map(String::from);
+                let job = self
+                    ._edit_job(&Job::default(), &fields, interaction)
+                    .await?;
+                let job = self.ags.add_job(&job).await?;
+                output::show_object(&job, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn get_job(&self, interaction: &dyn InteractionTrait) {
+        let id = self._select_job_id(interaction).await;
+
+        show_err(
+            async {
+                let job = self.ags.get_job(&id).await?;
+                output::show_object(&job, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn get_all_jobs(&self, interaction: &dyn InteractionTrait) {
+        let query = interaction.input_job_query();
+
+        show_err(
+            async {
+                let (filter, sort) = filter::parse_job_query(&query)?;
+                let jobs = self.ags.list_jobs().await?;
+                let total = jobs.len();
+                let jobs = filter::select_jobs(jobs, &filter, sort, Utc::now());
+                output::show_jobs(&jobs, total, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn update_job(&self, interaction: &dyn InteractionTrait) {
+        let id = self._select_job_id(interaction).await;
+
+        show_err(
+            async {
+                let current = self.ags.get_job(&id).await?;
+                let mut choices: Vec<String> = EDIT_FIELDS.map(String::from).to_vec();
+                if let Some(schedule) = schedule_field(&current.job_type) {
+                    choices.insert(2, schedule.to_string());
+                }
+                let fields = interaction.select_fields(choices);
+                let job = self._edit_job(&current, &fields, interaction).await?;
+
+                let changes = diff::jobs(&current, &job);
+                eprintln!("{}", output::format_job_diff(&id, &changes));
+                if changes.is_empty() {
+                    return Ok(());
+                }
+                let confirm = !self.ags.client.config.dry_run;
+                if confirm && !interaction.confirm_common(&format!("Update job `{}`?", job.name)) {
+                    return Ok(());
+                }
+                let job = self.ags.update_job(&job).await?;
+                output::show_object(&job, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn delete_job(&self, interaction: &dyn InteractionTrait) {
+        let id = self._select_job_id(interaction).await;
+
+        if !interaction.confirm_delete() {
+            return;
+        }
+
+        show_err(
+            self.ags
+                .delete_job(&id)
+                .await
+                .map(|_| output::show_ok(self.output)),
+        );
+    }
+
+    pub async fn delete_all_jobs(&self, interaction: &dyn InteractionTrait) {
+        if !interaction.confirm_delete() {
+            return;
+        }
+
+        show_err(
+            self.ags
+                .delete_all_jobs()
+                .await
+                .map(|_| output::show_ok(self.output)),
+        );
+    }
+
+    pub async fn pause_or_resume_job(&self, action: &str, interaction: &dyn InteractionTrait) {
+        let id = self._select_job_id(interaction).await;
+
+        let result = match action {
+            "pause" => self.ags.pause_job(&id).await,
+            _ => self.ags.resume_job(&id).await,
+        };
+        show_err(result.map(|_| output::show_ok(self.output)));
+    }
+
+    pub async fn run_or_schedule_job(&self, action: &str, interaction: &dyn InteractionTrait) {
+        let id = self._select_job_id(interaction).await;
+
+        show_err(
+            async {
+                let job = self.ags.get_job(&id).await?;
+                match action {
+                    "run" => self.ags.run_job(&job).await?,
+                    _ => self.ags.schedule_job(&job).await?,
+                }
+                output::show_ok(self.output);
+
+                Ok(())
+            }
+            .await,
+        );
+    }
+
+    pub async fn bulk_job_operation(&self, interaction: &dyn InteractionTrait) {
+        let selections = ["Pause", "Resume", "Delete", "Run"]
+            .map(String::from)
+            .to_vec();
+        let action = interaction.select_common("Select Action", selections, 0);
+        let query = interaction.input_job_query();
+
+        show_err(
+            async {
+                let action = BulkAction::from_str(&action, true).map_err(anyhow::Error::msg)?;
+                let (filter, _) = filter::parse_job_query(&query)?;
+                if filter.is_empty() {
+                    return Err(anyhow::anyhow!("Select the jobs with at least one filter"));
+                }
+
+                let (jobs, _) =
+                    bulk::select(self.ags.list_jobs().await?, &filter, None, Utc::now());
+                if jobs.is_empty() {
+                    println!("No jobs matched");
+                    return Ok(());
+                }
+                output::show_jobs(&jobs, jobs.len(), self.output)?;
+                let prompt = format!("{} {} jobs?", action.name(), jobs.len());
+                if !interaction.confirm_common(&prompt) {
+                    return Ok(());
+                }
+
+                let results = bulk::apply(&self.ags, action, jobs, BULK_CONCURRENCY).await;
+                output::show_job_results(&results, self.output)?;
+                output::check_results(&results)
+            }
+            .await,
+        );
+    }
+
+    pub async fn start_or_stop(&self, action: &str) {
+        let result = match action {
+            "start" => self.ags.start().await,
+            _ => self.ags.stop().await,
+        };
+        show_err(result.map(|_| output::show_ok(self.output)));
+    }
+
+    async fn _get_records(&self, job_id: &str, interaction: &dyn InteractionTrait) {
+        let page = interaction.input_page("");
+        let page_size = interaction.input_page_size("");
+        let query = interaction.input_record_query();
+
+        show_err(
+            async {
+                let filter = filter::parse_record_query(&query)?;
+                if page.eq_ignore_ascii_case("all") {
+                    return cli::list_all_records(
+                        &self.ags,
+                        job_id,
+                        &filter,
+                        page_size.parse()?,
+                        RECORDS_CONCURRENCY,
+                        None,
+                        self.output,
+                    )
+                    .await;
+                }
+
+                let mut records = self
+                    .ags
+                    .list_records(job_id, page.parse()?, page_size.parse()?)
+                    .await?;
+                records.res = filter.apply(records.res, Utc::now());
+                output::show_records(&records, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn get_records(&self, interaction: &dyn InteractionTrait) {
+        let job_id = interaction.input_job_id();
+        self._get_records(&job_id, interaction).await;
+    }
+
+    pub async fn get_all_records(&self, interaction: &dyn InteractionTrait) {
+        self._get_records("", interaction).await;
+    }
+
+    async fn _delete_records(&self, job_id: &str, interaction: &dyn InteractionTrait) {
+        if !interaction.confirm_delete() {
+            return;
+        }
+
+        show_err(
+            self.ags
+                .delete_records(job_id)
+                .await
+                .map(|_| output::show_ok(self.output)),
+        );
+    }
+
+    pub async fn delete_records(&self, interaction: &dyn InteractionTrait) {
+        let job_id = interaction.input_job_id();
+        self._delete_records(&job_id, interaction).await;
+    }
+
+    pub async fn delete_all_records(&self, interaction: &dyn InteractionTrait) {
+        self._delete_records("", interaction).await;
+    }
+
+    pub async fn get_info(&self) {
+        show_err(
+            async {
+                let info = self.ags.get_info().await?;
+                output::show_object(&info, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn get_funcs(&self) {
+        show_err(
+            async {
+                let funcs = self.ags.list_funcs().await?;
+                output::show_funcs(&funcs, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn get_queues(&self) {
+        show_err(
+            async {
+                let queues = self.ags.list_queues().await?;
+                output::show_queues(&queues, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn get_cluster_nodes(&self) {
+        show_err(
+            async {
+                let nodes = self.ags.list_cluster_nodes().await?;
+                output::show_cluster_nodes(&nodes, self.output)
+            }
+            .await,
+        );
+    }
+
+    pub async fn dashboard(&self) {
+        show_err(dashboard::run(&self.ags, Duration::from_secs(2)).await);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::http;
+    use crate::interaction::MockInteractionTrait;
+
+    #[tokio::test]
+    async fn it_menu() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let id = String::from("00227fbf671f4ed2");
+        let job_id = String::from("b1638cfb7a8d4247");
+        let empty_data = json!({"data": null, "error": ""}).to_string();
+        let job_data = json!({
+            "data":  {
+                "args": {
+
+                },
+                "cron_expr": "",
+                "end_at": "",
+                "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
+                "id": "00227fbf671f4ed2",
+                "interval": "60s",
+                "last_run_time": "0001-01-01T00:00:00Z",
+                "name": "myJob",
+                "next_run_time": "2024-04-15T04:19:12Z",
+                "queues": [],
+                "start_at": "",
+                "status": "running",
+                "timeout": "1h",
+                "timezone": "UTC",
+                "type": "interval"
+            },
+            "error": ""
+        })
+        .to_string();
+
+        let page = String::from("1");
+        let page_size = String::from("10");
+
+        server
+            .mock("POST", "/scheduler/job")
+            .with_status(200)
+            .with_body(&job_data)
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/scheduler/job/{}", id).as_str())
+            .with_status(200)
+            .with_body(&job_data)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/scheduler/jobs")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": [
+                        {
+                            "args": {
+
+                            },
+                            "cron_expr": "",
+                            "end_at": "",
+                            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
+                            "id": "00227fbf671f4ed2",
+                            "interval": "60s",
+                            "last_run_time": "0001-01-01T00:00:00Z",
+                            "name": "myJob",
+                            "next_run_time": "2024-04-15T04:19:12Z",
+                            "queues": [],
+                            "start_at": "",
+                            "status": "running",
+                            "timeout": "1h",
+                            "timezone": "UTC",
+                            "type": "interval"
+                        },
+                        {
+                            "args": {
+
+                            },
+                            "cron_expr": "",
+                            "end_at": "",
+                            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
+                            "id": "5hy65y56yh65y56h",
+                            "interval": "",
+                            "last_run_time": "0001-01-01T00:00:00Z",
+                            "name": "myJob2",
+                            "next_run_time": "2024-04-15T04:19:12Z",
+                            "queues": [],
+                            "start_at": "2024-05-16 17:16:08",
+                            "status": "running",
+                            "timeout": "1h",
+                            "timezone": "UTC",
+                            "type": "datetime"
+                        },
+                        {
+                            "args": {
+
+                            },
+                            "cron_expr": "*/1 * * * *",
+                            "func_name": "github.com/agscheduler/agscheduler/examples.PrintMsg",
+                            "id": "n4yhb56j3gj45h56",
+                            "interval": "",
+                            "last_run_time": null,
+                            "name": "myJob3",
+                            "next_run_time": "2024-04-15T04:19:12Z",
+                            "queues": [],
+                            "start_at": "",
+                            "status": "running",
+                            "timeout": "1h",
+                            "timezone": "UTC",
+                            "type": "cron"
+                        }
+                    ],
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("PUT", "/scheduler/job")
+            .with_status(200)
+            .with_body(&job_data)
+            .create_async()
+            .await;
+        server
+            .mock("DELETE", format!("/scheduler/job/{}", id).as_str())
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("DELETE", "/scheduler/jobs")
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("POST", format!("/scheduler/job/{}/pause", id).as_str())
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("POST", format!("/scheduler/job/{}/resume", id).as_str())
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/scheduler/job/run")
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/scheduler/job/schedule")
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/scheduler/start")
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/scheduler/stop")
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock(
+                "GET",
+                format!(
+                    "/recorder/records/{}?page={}&page_size={}",
+                    job_id, page, page_size
+                )
+                .as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": {
+                        "page": 1,
+                        "page_size": 10,
+                        "res": [
+                            {
+                                "id": 516544388,
+                                "job_id": "b1638cfb7a8d4247",
+                                "job_name": "myJob5",
+                                "status": "completed",
+                                "result": "",
+                                "start_at": "2024-06-03T11:27:28.002Z",
+                                "end_at": "2024-06-03T11:27:28.027Z"
+                            }
+                        ],
+                        "total": 1
+                    },
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock(
+                "GET",
+                format!("/recorder/records?page={}&page_size={}", page, page_size).as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": {
+                        "page": 1,
+                        "page_size": 10,
+                        "res": [
+                            {
+                                "id": 516544388,
+                                "job_id": "b1638cfb7a8d4247",
+                                "job_name": "myJob5",
+                                "status": "completed",
+                                "result": "",
+                                "start_at": "2024-06-03T11:27:28.002Z",
+                                "end_at": "2024-06-03T11:27:28.027Z"
+                            },
+                            {
+                                "id": 516541097,
+                                "job_id": "e99532afe9f44e63",
+                                "job_name": "myJob4",
+                                "status": "error",
+                                "result": "error: something error",
+                                "start_at": "2024-06-03T10:54:46.034Z",
+                                "end_at": "2024-06-03T10:54:51.069Z"
+                            }
+                        ],
+                        "total": 2
+                    },
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("DELETE", format!("/recorder/records/{}", job_id).as_str())
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("DELETE", "/recorder/records")
+            .with_status(200)
+            .with_body(&empty_data)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/info")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": {
+                        "cluster_main_node": {
+                            "endpoint": "127.0.0.1:36380",
+                            "endpoint_grpc": "127.0.0.1:36360",
+                            "endpoint_http": "127.0.0.1:36370",
+                            "endpoint_main": "127.0.0.1:36380",
+                            "mode": ""
+                        },
+                        "is_cluster_mode": true,
+                        "is_running": false,
+                        "version": "0.6.1"
+                    },
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/funcs")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": [
+                        {
+                            "info": "",
+                            "name": "github.com/agscheduler/agscheduler/examples.PrintMsg"
+                        }
+                    ],
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/broker/queues")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": [
+                        {
+                            "name": "default",
+                            "type": "Memory",
+                            "count": 1,
+                            "workers": 2,
+                        },
+                    ],
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/cluster/nodes")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": {
+                        "127.0.0.1:36380": {
+                            "endpoint": "127.0.0.1:36380",
+                            "endpoint_grpc": "127.0.0.1:36360",
+                            "endpoint_http": "127.0.0.1:36370",
+                            "endpoint_main": "127.0.0.1:36380",
+                            "health": true,
+                            "last_heartbeat_time": "2024-04-15T04:30:08.489043439Z",
+                            "mode": "",
+                            "queue": "default",
+                            "register_time": "2024-04-15T04:08:10.438222846Z",
+                            "version": "0.6.1"
+                        }
+                    },
+                    "error": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut mock = MockInteractionTrait::new();
+        mock.expect_input_id().return_const(id.clone());
+        mock.expect_select_job().return_const(id);
+        mock.expect_input_job_id().return_const(job_id);
+        mock.expect_confirm_delete().return_const(true);
+        mock.expect_confirm_common().return_const(true);
+        mock.expect_select_common().return_const("Pause");
+        mock.expect_input_job_query()
+            .return_const("status=running sort=name");
+        mock.expect_input_record_query().return_const("");
+        mock.expect_input_name().return_const("myJob");
+        mock.expect_input_start_at()
+            .return_const("2024-04-16 15:23:51");
+        mock.expect_input_interval().return_const("60s");
+        mock.expect_input_cron_expr().return_const("*/1 * * * *");
+        mock.expect_input_timezone().return_const("UTC");
+        mock.expect_input_args().return_const("{}");
+        mock.expect_input_timeout().return_const("1h");
+        mock.expect_input_queues().return_const("[]");
+        mock.expect_input_page().return_const(page);
+        mock.expect_input_page_size().return_const(page_size);
+        mock.expect_select_type().return_const("Interval");
+        mock.expect_select_fields()
+            .return_const(vec!["name".to_string(), "interval".to_string()]);
+        mock.expect_select_func_name()
+            .return_const("github.com/agscheduler/agscheduler/examples.PrintMsg");
+
+        let menu = Menu {
+            ags: AGScheduler::new(http::Config::new(&url)).unwrap(),
+            output: OutputFormat::Table,
+        };
+
+        menu.add_job(&mock).await;
+        menu.get_job(&mock).await;
+        menu.get_all_jobs(&mock).await;
+        menu.update_job(&mock).await;
+        menu.delete_job(&mock).await;
+        menu.delete_all_jobs(&mock).await;
+        menu.pause_or_resume_job("pause", &mock).await;
+        menu.pause_or_resume_job("resume", &mock).await;
+        menu.run_or_schedule_job("run", &mock).await;
+        menu.run_or_schedule_job("schedule", &mock).await;
+        menu.bulk_job_operation(&mock).await;
+        menu.start_or_stop("start").await;
+        menu.start_or_stop("stop").await;
+        menu.get_records(&mock).await;
+        menu.get_all_records(&mock).await;
+        menu.delete_records(&mock).await;
+        menu.delete_all_records(&mock).await;
+        menu.get_info().await;
+        menu.get_funcs().await;
+        menu.get_queues().await;
+        menu.get_cluster_nodes().await;
+
+        for output in [
+            OutputFormat::Json,
+            OutputFormat::Yaml,
+            OutputFormat::Csv,
+            OutputFormat::Ndjson,
+        ] {
+            let menu = Menu {
+                ags: AGScheduler::new(http::Config::new(&server.url())).unwrap(),
+                output,
+            };
+
+            menu.get_job(&mock).await;
+            menu.get_all_jobs(&mock).await;
+            menu.get_all_records(&mock).await;
+            menu.get_info().await;
+            menu.get_funcs().await;
+            menu.get_queues().await;
+            menu.get_cluster_nodes().await;
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Treats `null` like a missing field, the server encodes empty Go slices and maps as `null`.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Job {
+    #[serde(deserialize_with = "null_as_default")]
+    pub id: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub name: String,
+    #[serde(rename = "type", deserialize_with = "null_as_default")]
+    pub job_type: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub start_at: String,
+    pub end_at: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub interval: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub cron_expr: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub timezone: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub func_name: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub args: Map<String, Value>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub timeout: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub queues: Vec<String>,
+    pub last_run_time: Option<String>,
+    pub next_run_time: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub status: String,
+    /// Fields added by newer servers, kept so they survive a round trip.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Job {
+    /// Returns the value of the field matching the job type.
+    pub fn type_value(&self) -> &str {
+        match self.job_type.as_str() {
+            "datetime" => &self.start_at,
+            "interval" => &self.interval,
+            "cron" => &self.cron_expr,
+            _ => "",
+        }
+    }
+
+    /// Returns the body accepted by `/scheduler/job` and its run/schedule endpoints.
+    pub fn to_body(&self) -> Value {
+        json!(
+            {
+                "id": self.id,
+                "name": self.name,
+                "type": self.job_type,
+                "start_at": self.start_at,
+                "interval": self.interval,
+                "cron_expr": self.cron_expr,
+                "timezone": self.timezone,
+                "func_name": self.func_name,
+                "args": self.args,
+                "timeout": self.timeout,
+                "queues": self.queues,
+            }
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Record {
+    #[serde(deserialize_with = "null_as_default")]
+    pub id: u64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub job_id: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub job_name: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub status: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub result: String,
+    pub start_at: Option<String>,
+    /// Not set while the record is `running`.
+    pub end_at: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordPage {
+    #[serde(deserialize_with = "null_as_default")]
+    pub page: u64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub page_size: u64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub total: u64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub res: Vec<Record>,
+}
+
+impl RecordPage {
+    pub fn page_count(&self) -> u64 {
+        if self.page_size == 0 {
+            return 0;
+        }
+        self.total.div_ceil(self.page_size)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Func {
+    #[serde(deserialize_with = "null_as_default")]
+    pub name: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub info: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Queue {
+    #[serde(deserialize_with = "null_as_default")]
+    pub name: String,
+    #[serde(rename = "type", deserialize_with = "null_as_default")]
+    pub queue_type: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub count: i64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub workers: i64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterNode {
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint_grpc: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint_http: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint_main: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub queue: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub mode: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub version: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub health: bool,
+    pub register_time: Option<String>,
+    pub last_heartbeat_time: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ClusterNode {
+    pub fn is_leader(&self) -> bool {
+        self.endpoint == self.endpoint_main
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterMainNode {
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint_grpc: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint_http: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub endpoint_main: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub mode: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Info {
+    /// Only set in cluster mode.
+    pub cluster_main_node: Option<ClusterMainNode>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub is_cluster_mode: bool,
+    #[serde(deserialize_with = "null_as_default")]
+    pub is_running: bool,
+    #[serde(deserialize_with = "null_as_default")]
+    pub version: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_job_nulls_and_missing_fields() {
+        let job: Job = serde_json::from_value(json!({
+            "id": "00227fbf671f4ed2",
+            "name": "myJob",
+            "type": "interval",
+            "interval": "60s",
+            "args": null,
+            "queues": null,
+            "last_run_time": null,
+            "status": "running"
+        }))
+        .unwrap();
+
+        assert_eq!("60s", job.type_value());
+        assert!(job.args.is_empty());
+        assert!(job.queues.is_empty());
+        assert_eq!(None, job.last_run_time);
+        assert_eq!(None, job.next_run_time);
+        assert_eq!(None, job.end_at);
+        assert_eq!("", job.timezone);
+    }
+
+    #[test]
+    fn it_numbers_and_bools_from_null() {
+        let record: Record =
+            serde_json::from_value(json!({"id": null, "status": "running"})).unwrap();
+        assert_eq!(0, record.id);
+
+        let page: RecordPage = serde_json::from_value(
+            json!({"page": null, "page_size": null, "total": null, "res": [{"id": null}]}),
+        )
+        .unwrap();
+        assert_eq!(
+            (0, 0, 0, 1),
+            (page.page, page.page_size, page.total, page.res.len())
+        );
+
+        let queue: Queue =
+            serde_json::from_value(json!({"name": "default", "count": null, "workers": null}))
+                .unwrap();
+        assert_eq!((0, 0), (queue.count, queue.workers));
+
+        let node: ClusterNode = serde_json::from_value(json!({"health": null})).unwrap();
+        assert!(!node.health);
+
+        let info: Info =
+            serde_json::from_value(json!({"is_cluster_mode": null, "is_running": null})).unwrap();
+        assert!(!info.is_cluster_mode && !info.is_running);
+    }
+
+    #[test]
+    fn it_job_unknown_fields() {
+        let value = json!({
+            "id": "00227fbf671f4ed2",
+            "name": "myJob",
+            "type": "cron",
+            "cron_expr": "*/1 * * * *",
+            "args": {"arg1": "1"},
+            "queues": ["default"],
+            "new_field": 1
+        });
+        let job: Job = serde_json::from_value(value).unwrap();
+
+        assert_eq!("*/1 * * * *", job.type_value());
+        assert_eq!(json!(1), job.extra["new_field"]);
+        assert_eq!(json!(1), serde_json::to_value(&job).unwrap()["new_field"]);
+        assert_eq!(
+            json!({
+                "id": "00227fbf671f4ed2",
+                "name": "myJob",
+                "type": "cron",
+                "start_at": "",
+                "interval": "",
+                "cron_expr": "*/1 * * * *",
+                "timezone": "",
+                "func_name": "",
+                "args": {"arg1": "1"},
+                "timeout": "",
+                "queues": ["default"],
+            }),
+            job.to_body()
+        );
+    }
+
+    #[test]
+    fn it_record_page() {
+        let page: RecordPage = serde_json::from_value(json!({
+            "page": 1,
+            "page_size": 10,
+            "res": [
+                {
+                    "id": 516544388,
+                    "job_id": "b1638cfb7a8d4247",
+                    "job_name": "myJob5",
+                    "status": "running",
+                    "result": "",
+                    "start_at": "2024-06-03T11:27:28.002Z",
+                }
+            ],
+            "total": 21
+        }))
+        .unwrap();
+
+        assert_eq!(3, page.page_count());
+        assert_eq!(None, page.res[0].end_at);
+        assert_eq!(0, RecordPage::default().page_count());
+    }
+
+    #[test]
+    fn it_info_and_cluster_node() {
+        let info: Info = serde_json::from_value(json!({
+            "cluster_main_node": null,
+            "is_cluster_mode": false,
+            "is_running": true,
+            "version": "0.6.1"
+        }))
+        .unwrap();
+        assert_eq!(None, info.cluster_main_node);
+
+        let node: ClusterNode = serde_json::from_value(json!({
+            "endpoint": "127.0.0.1:36380",
+            "endpoint_main": "127.0.0.1:36380",
+            "health": true
+        }))
+        .unwrap();
+        assert!(node.is_leader());
+        assert_eq!(None, node.register_time);
+    }
+}
@@ -0,0 +1,886 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone};
+use clap::ValueEnum;
+use comfy_table::{ContentArrangement, Table};
+use console::style;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::apply::{Action, Change};
+use crate::datetime;
+use crate::diff::FieldChange;
+use crate::http::DryRun;
+use crate::models::{ClusterNode, Func, Job, Queue, Record, RecordPage};
+use crate::stats::JobStats;
+use crate::timezone::Zone;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+    Ndjson,
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.to_string(),
+        v => v.to_string(),
+    }
+}
+
+/// Formats a list of rows, nested values are written as JSON in `csv`,
+/// where rows that are not objects go in a `value` column.
+pub fn format_list<T: Serialize>(list: &[T], format: OutputFormat) -> anyhow::Result<String> {
+    let list: Vec<Value> = list
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<_, _>>()?;
+    let output = match format {
+        OutputFormat::Table | OutputFormat::Json => serde_json::to_string_pretty(&list)?,
+        OutputFormat::Yaml => serde_yaml::to_string(&list)?,
+        OutputFormat::Ndjson => list
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>()
+            .join("\n"),
+        OutputFormat::Csv => {
+            let mut columns: Vec<String> = vec![];
+            for row in &list {
+                let keys: Vec<String> = match row {
+                    Value::Object(map) => map.keys().cloned().collect(),
+                    _ => vec!["value".to_string()],
+                };
+                for key in keys {
+                    if !columns.contains(&key) {
+                        columns.push(key);
+                    }
+                }
+            }
+            if columns.is_empty() {
+                return Ok(String::new());
+            }
+
+            let mut writer = csv::Writer::from_writer(vec![]);
+            writer.write_record(&columns)?;
+            for row in &list {
+                writer.write_record(columns.iter().map(|c| match row {
+                    Value::Object(_) => cell(&row[c]),
+                    _ if c == "value" => cell(row),
+                    _ => String::new(),
+                }))?;
+            }
+            String::from_utf8(writer.into_inner()?)?
+                .trim_end()
+                .to_string()
+        }
+    };
+
+    Ok(output)
+}
+
+/// Formats a single object, as a `Field`/`Value` table in `table`.
+pub fn format_object<T: Serialize>(value: &T, format: OutputFormat) -> anyhow::Result<String> {
+    let value = serde_json::to_value(value)?;
+    let output = match format {
+        OutputFormat::Table => {
+            let mut table = Table::new();
+            table
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec!["Field", "Value"]);
+            if let Value::Object(map) = &value {
+                for (key, v) in map {
+                    table.add_row(vec![key.to_string(), cell(v)]);
+                }
+            }
+            table.to_string()
+        }
+        OutputFormat::Json => serde_json::to_string_pretty(&value)?,
+        OutputFormat::Yaml => serde_yaml::to_string(&value)?,
+        OutputFormat::Ndjson => value.to_string(),
+        OutputFormat::Csv => format_list(&[value], format)?,
+    };
+
+    Ok(output)
+}
+
+pub fn show_list<T: Serialize>(list: &[T], format: OutputFormat) -> anyhow::Result<()> {
+    let output = format_list(list, format)?;
+    // An empty CSV or ndjson document has no lines at all.
+    if !output.is_empty() {
+        println!("{output}");
+    }
+
+    Ok(())
+}
+
+pub fn show_object<T: Serialize>(value: &T, format: OutputFormat) -> anyhow::Result<()> {
+    println!("{}", format_object(value, format)?);
+
+    Ok(())
+}
+
+/// Prints `Ok` after a successful operation, machine formats stay silent.
+pub fn show_ok(format: OutputFormat) {
+    if format == OutputFormat::Table {
+        println!("Ok");
+    }
+}
+
+/// Prints a request kept by `--dry-run`, as text for the table output.
+pub fn show_dry_run(request: &DryRun, format: OutputFormat) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Table => println!("{request}"),
+        _ => show_object(request, format)?,
+    }
+
+    Ok(())
+}
+
+/// Lists the changes to a job one per line, meant for stderr and coloured when it is a terminal.
+pub fn format_job_diff(id: &str, changes: &[FieldChange]) -> String {
+    if changes.is_empty() {
+        return format!("No changes to job `{id}`");
+    }
+
+    let mut lines = vec![format!("Changes to job `{id}`:")];
+    for change in changes {
+        lines.push(
+            match change {
+                FieldChange::Added { path, new } => {
+                    style(format!("+ {path}: {new}")).for_stderr().green()
+                }
+                FieldChange::Removed { path, old } => {
+                    style(format!("- {path}: {old}")).for_stderr().red()
+                }
+                FieldChange::Changed { path, old, new } => {
+                    style(format!("~ {path}: {old} -> {new}"))
+                        .for_stderr()
+                        .yellow()
+                }
+            }
+            .to_string(),
+        );
+    }
+
+    lines.join("\n")
+}
+
+/// Prints warnings to stderr, so they never mix with machine output.
+pub fn show_warnings(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("Warning: {warning}");
+    }
+}
+
+pub fn jobs_table(jobs: &[Job]) -> Table {
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            "ID",
+            "Name",
+            "Type",
+            "TypeValue",
+            "LastRunTime",
+            "NextRunTime",
+            "Status",
+        ]);
+
+    for j in jobs {
+        table.add_row(vec![
+            &j.id,
+            &j.name,
+            &j.job_type,
+            j.type_value(),
+            &datetime::format_local(j.last_run_time.as_deref()),
+            &datetime::format_local(j.next_run_time.as_deref()),
+            &j.status,
+        ]);
+    }
+
+    table
+}
+
+/// `Total` line of a job list, with the number matched when it was filtered.
+pub fn jobs_footer(matched: usize, total: usize) -> String {
+    if matched == total {
+        return format!("Total {total}");
+    }
+
+    format!("Total {matched} of {total}")
+}
+
+/// Shows the jobs left out of `total` after filtering.
+pub fn show_jobs(jobs: &[Job], total: usize, format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(jobs, format);
+    }
+
+    println!("{}", jobs_table(jobs));
+    println!("{}", jobs_footer(jobs.len(), total));
+
+    Ok(())
+}
+
+/// Lists jobs about to be changed on stderr, whatever the output format, so they are never confirmed blind.
+pub fn preview_jobs(jobs: &[Job]) {
+    eprintln!("{}", jobs_table(jobs));
+    eprintln!("{}", jobs_footer(jobs.len(), jobs.len()));
+}
+
+const RECORD_HEADER: [&str; 7] = [
+    "ID", "JobName", "JobId", "Status", "StartAt", "EndAt", "Result",
+];
+
+fn record_row(r: &Record) -> [String; 7] {
+    let mut end_at = String::from("");
+    if r.status != "running" {
+        end_at = datetime::format_local(r.end_at.as_deref());
+    }
+
+    [
+        r.id.to_string(),
+        r.job_name.to_string(),
+        r.job_id.to_string(),
+        r.status.to_string(),
+        datetime::format_local(r.start_at.as_deref()),
+        end_at,
+        r.result.to_string(),
+    ]
+}
+
+pub fn records_table(records: &RecordPage) -> Table {
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(RECORD_HEADER);
+
+    for r in &records.res {
+        table.add_row(record_row(r));
+    }
+
+    table
+}
+
+/// Widths of the streamed record columns but the last, wide enough for typical values
+/// since rows are printed before the next ones are known.
+const RECORD_WIDTHS: [usize; 6] = [10, 16, 16, 8, 19, 19];
+
+/// Formats a streamed record row, padding each column but the last to its width.
+fn record_line<S: AsRef<str>>(fields: &[S; 7]) -> String {
+    let mut line = String::new();
+    for (field, width) in fields.iter().zip(RECORD_WIDTHS) {
+        line.push_str(&format!("{:<width$}  ", field.as_ref()));
+    }
+    line.push_str(fields[6].as_ref());
+
+    line.trim_end().to_string()
+}
+
+/// Footer of streamed records, `matched` out of the `total` records of the server.
+pub fn records_total(matched: usize, total: usize) -> String {
+    if matched == total {
+        return format!("Total {total} records");
+    }
+
+    format!("Total {matched} of {total} records")
+}
+
+pub fn show_records(records: &RecordPage, format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(&records.res, format);
+    }
+
+    println!("{}", records_table(records));
+    println!("{}", records_footer(records));
+
+    Ok(())
+}
+
+pub fn records_footer(records: &RecordPage) -> String {
+    format!(
+        "Page {}/{}  PageSize {}  Total {}",
+        records.page,
+        records.page_count(),
+        records.page_size,
+        records.total
+    )
+}
+
+/// Prints records as their pages are fetched. Table and ndjson rows are streamed,
+/// tables as aligned rows under a single header, the other formats are single documents
+/// printed by `finish`.
+pub struct RecordStream {
+    format: OutputFormat,
+    count: usize,
+    buffered: Vec<Record>,
+}
+
+impl RecordStream {
+    pub fn new(format: OutputFormat) -> Self {
+        RecordStream {
+            format,
+            count: 0,
+            buffered: vec![],
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn push(&mut self, records: Vec<Record>) -> anyhow::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        match self.format {
+            OutputFormat::Table => {
+                if self.count == 0 {
+                    println!("{}", record_line(&RECORD_HEADER));
+                }
+                for r in &records {
+                    println!("{}", record_line(&record_row(r)));
+                }
+            }
+            OutputFormat::Ndjson => println!("{}", format_list(&records, self.format)?),
+            _ => self.buffered.extend_from_slice(&records),
+        }
+        self.count += records.len();
+
+        Ok(())
+    }
+
+    /// Prints the buffered documents, or the `Total` line of a table.
+    pub fn finish(self, total: u64) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => println!("{}", records_total(self.count, total as usize)),
+            OutputFormat::Ndjson => {}
+            _ => println!("{}", format_list(&self.buffered, self.format)?),
+        }
+
+        Ok(())
+    }
+}
+
+fn format_secs(secs: Option<f64>) -> String {
+    match secs {
+        Some(secs) => format!(
+            "{:?}",
+            Duration::from_millis((secs * 1000.0).round() as u64)
+        ),
+        None => String::new(),
+    }
+}
+
+pub fn show_stats(stats: &[JobStats], format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(stats, format);
+    }
+
+    let percent = |rate: f64| format!("{:.1}%", rate * 100.0);
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            "JobName",
+            "JobId",
+            "Runs",
+            "Success",
+            "Error",
+            "Timeout",
+            "Min",
+            "Avg",
+            "P95",
+            "Max",
+            "LastFailure",
+        ]);
+    for s in stats {
+        table.add_row(vec![
+            s.job_name.to_string(),
+            s.job_id.to_string(),
+            s.runs.to_string(),
+            percent(s.success_rate),
+            percent(s.error_rate),
+            percent(s.timeout_rate),
+            format_secs(s.min_secs),
+            format_secs(s.avg_secs),
+            format_secs(s.p95_secs),
+            format_secs(s.max_secs),
+            datetime::format_local(s.last_failure.as_deref()),
+        ]);
+    }
+
+    println!("{table}");
+    println!(
+        "Total {} jobs  {} runs",
+        stats.len(),
+        stats.iter().map(|s| s.runs).sum::<usize>()
+    );
+
+    Ok(())
+}
+
+pub fn show_funcs(funcs: &[Func], format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(funcs, format);
+    }
+
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["name", "info"]);
+
+    for f in funcs {
+        table.add_row(vec![&f.name, &f.info]);
+    }
+
+    println!("{table}");
+    println!("Total {}", funcs.len());
+
+    Ok(())
+}
+
+pub fn queues_table(queues: &[Queue]) -> Table {
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Name", "Type", "Count", "Workers"]);
+
+    for q in queues {
+        table.add_row(vec![
+            &q.name,
+            &q.queue_type,
+            &q.count.to_string(),
+            &q.workers.to_string(),
+        ]);
+    }
+
+    table
+}
+
+pub fn show_queues(queues: &[Queue], format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(queues, format);
+    }
+
+    println!("{}", queues_table(queues));
+    println!("Total {}", queues.len());
+
+    Ok(())
+}
+
+pub fn show_timezones(zones: &[Zone], format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(zones, format);
+    }
+
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Name", "Offset"]);
+    for z in zones {
+        table.add_row(vec![&z.name, &z.offset]);
+    }
+    println!("{table}");
+    println!("Total {}", zones.len());
+
+    Ok(())
+}
+
+pub fn cluster_nodes_table(nodes: &[ClusterNode]) -> Table {
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Endpoint",
+            "Leader",
+            "EndpointGRPC",
+            "EndpointHTTP",
+            "EndpointMain",
+            "Queue",
+            "Mode",
+            "Version",
+            "Health",
+            "RegisterTime",
+            "LastHeartbeatTime",
+        ]);
+
+    for n in nodes {
+        table.add_row(vec![
+            &n.endpoint,
+            &n.is_leader().to_string(),
+            &n.endpoint_grpc,
+            &n.endpoint_http,
+            &n.endpoint_main,
+            &n.queue,
+            &n.mode,
+            &n.version,
+            &n.health.to_string(),
+            &datetime::format_local(n.register_time.as_deref()),
+            &datetime::format_local(n.last_heartbeat_time.as_deref()),
+        ]);
+    }
+
+    table
+}
+
+pub fn show_cluster_nodes(nodes: &[ClusterNode], format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(nodes, format);
+    }
+
+    println!("{}", cluster_nodes_table(nodes));
+    println!("Total {}", nodes.len());
+
+    Ok(())
+}
+
+pub fn show_fire_times<Tz: TimeZone>(
+    times: &[DateTime<Tz>],
+    format: OutputFormat,
+) -> anyhow::Result<()>
+where
+    Tz::Offset: Display,
+{
+    if format != OutputFormat::Table {
+        return show_list(&fire_time_rows(times), format);
+    }
+
+    println!("{}", format_fire_times(times));
+
+    Ok(())
+}
+
+/// Rows of the machine formats, one `time` object per fire time.
+fn fire_time_rows<Tz: TimeZone>(times: &[DateTime<Tz>]) -> Vec<Value>
+where
+    Tz::Offset: Display,
+{
+    times
+        .iter()
+        .map(|t| serde_json::json!({"time": t.to_rfc3339()}))
+        .collect()
+}
+
+/// Lists fire times one per line, with their UTC offset.
+pub fn format_fire_times<Tz: TimeZone>(times: &[DateTime<Tz>]) -> String
+where
+    Tz::Offset: Display,
+{
+    if times.is_empty() {
+        return "Never fires".to_string();
+    }
+
+    let mut lines = vec!["Next fire times:".to_string()];
+    for t in times {
+        lines.push(format!("  {}", t.format("%Y-%m-%d %H:%M:%S %:z")));
+    }
+    lines.join("\n")
+}
+
+/// Outcome of an operation on one job, `result` is `ok` or the error.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct JobResult {
+    pub id: String,
+    pub name: String,
+    pub result: String,
+}
+
+/// Result of an operation whose request was only printed by `--dry-run`.
+pub const DRY_RUN_RESULT: &str = "dry run";
+
+impl JobResult {
+    /// Result of an operation that returned `err`, a dry run is not a failure.
+    pub fn error(id: String, name: String, err: &anyhow::Error) -> Self {
+        let result = match err.is::<DryRun>() {
+            true => DRY_RUN_RESULT.to_string(),
+            false => err.to_string(),
+        };
+        JobResult { id, name, result }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.result != "ok" && self.result != DRY_RUN_RESULT
+    }
+}
+
+/// Fails if any of the results failed, so scripts see partial failures.
+pub fn check_results(results: &[JobResult]) -> anyhow::Result<()> {
+    let failed = results.iter().filter(|r| r.failed()).count();
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{failed} of {} jobs failed", results.len()));
+    }
+
+    Ok(())
+}
+
+/// Labels jobs for a picker as aligned `name  type  func  status  id` columns.
+pub fn job_choices(jobs: &[Job]) -> Vec<String> {
+    let width = |field: fn(&Job) -> &str| jobs.iter().map(|j| field(j).chars().count()).max();
+    let name = width(|j| &j.name).unwrap_or_default();
+    let job_type = width(|j| &j.job_type).unwrap_or_default();
+    let func_name = width(|j| &j.func_name).unwrap_or_default();
+    let status = width(|j| &j.status).unwrap_or_default();
+
+    jobs.iter()
+        .map(|j| {
+            format!(
+                "{:name$}  {:job_type$}  {:func_name$}  {:status$}  {}",
+                j.name, j.job_type, j.func_name, j.status, j.id
+            )
+        })
+        .collect()
+}
+
+pub fn show_job_results(results: &[JobResult], format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(results, format);
+    }
+
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["ID", "Name", "Result"]);
+
+    for r in results {
+        table.add_row(vec![&r.id, &r.name, &r.result]);
+    }
+
+    println!("{table}");
+    println!(
+        "Ok {}  Failed {}",
+        results.iter().filter(|r| r.result == "ok").count(),
+        results.iter().filter(|r| r.failed()).count()
+    );
+
+    Ok(())
+}
+
+pub fn plan_table(changes: &[Change]) -> Table {
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Action", "ID", "Name", "Changes"]);
+
+    for c in changes {
+        let action = match c.action {
+            Action::Create => "+ create",
+            Action::Update => "~ update",
+            Action::Unchanged => "  unchanged",
+        };
+        table.add_row(vec![action, &c.job.id, &c.job.name, &c.fields.join(", ")]);
+    }
+
+    table
+}
+
+pub fn show_plan(changes: &[Change], format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Table {
+        return show_list(changes, format);
+    }
+
+    let count = |action| changes.iter().filter(|c| c.action == action).count();
+    println!("{}", plan_table(changes));
+    println!(
+        "Create {}  Update {}  Unchanged {}",
+        count(Action::Create),
+        count(Action::Update),
+        count(Action::Unchanged)
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_format_list() {
+        let list = vec![
+            json!({"name": "default", "count": 1, "args": {"a": "b"}}),
+            json!({"name": "q,2", "count": 2, "end_at": null}),
+        ];
+
+        assert_eq!(
+            "args,count,name,end_at\n\"{\"\"a\"\":\"\"b\"\"}\",1,default,\n,2,\"q,2\",",
+            format_list(&list, OutputFormat::Csv).unwrap()
+        );
+        assert_eq!(
+            "",
+            format_list(&Vec::<Value>::new(), OutputFormat::Csv).unwrap()
+        );
+        assert_eq!(
+            "value\nAsia/Shanghai\n1",
+            format_list(&[json!("Asia/Shanghai"), json!(1)], OutputFormat::Csv).unwrap()
+        );
+        assert_eq!(
+            "{\"args\":{\"a\":\"b\"},\"count\":1,\"name\":\"default\"}\n{\"count\":2,\"end_at\":null,\"name\":\"q,2\"}",
+            format_list(&list, OutputFormat::Ndjson).unwrap()
+        );
+        assert_eq!(
+            "- args:\n    a: b\n  count: 1\n  name: default\n- count: 2\n  end_at: null\n  name: q,2\n",
+            format_list(&list, OutputFormat::Yaml).unwrap()
+        );
+        assert_eq!(
+            list,
+            serde_json::from_str::<Vec<Value>>(&format_list(&list, OutputFormat::Json).unwrap())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_fire_time_rows() {
+        let tz = chrono::FixedOffset::east_opt(8 * 3600).unwrap();
+        let times = [
+            tz.with_ymd_and_hms(2024, 4, 15, 9, 0, 0).unwrap(),
+            tz.with_ymd_and_hms(2024, 4, 16, 9, 0, 0).unwrap(),
+        ];
+        assert_eq!(
+            "time\n2024-04-15T09:00:00+08:00\n2024-04-16T09:00:00+08:00",
+            format_list(&fire_time_rows(&times), OutputFormat::Csv).unwrap()
+        );
+        assert_eq!(
+            "",
+            format_list(&fire_time_rows(&times[..0]), OutputFormat::Csv).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_format_object() {
+        let value = json!({"version": "0.6.1", "is_running": false});
+
+        let table = format_object(&value, OutputFormat::Table).unwrap();
+        assert!(table.contains("is_running"));
+        assert!(table.contains("0.6.1"));
+        assert_eq!(
+            "is_running,version\nfalse,0.6.1",
+            format_object(&value, OutputFormat::Csv).unwrap()
+        );
+        assert_eq!(
+            "{\"is_running\":false,\"version\":\"0.6.1\"}",
+            format_object(&value, OutputFormat::Ndjson).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_format_secs() {
+        assert_eq!("1.5s", format_secs(Some(1.5)));
+        assert_eq!("250ms", format_secs(Some(0.25)));
+        assert_eq!("", format_secs(None));
+    }
+
+    #[test]
+    fn it_format_job_diff() {
+        assert_eq!("No changes to job `x`", format_job_diff("x", &[]));
+
+        let changes = [
+            FieldChange::Added {
+                path: "args.a".to_string(),
+                new: json!(1),
+            },
+            FieldChange::Removed {
+                path: "queues".to_string(),
+                old: json!("default"),
+            },
+            FieldChange::Changed {
+                path: "interval".to_string(),
+                old: json!("60s"),
+                new: json!("30s"),
+            },
+        ];
+        assert_eq!(
+            "Changes to job `x`:\n+ args.a: 1\n- queues: \"default\"\n~ interval: \"60s\" -> \"30s\"",
+            console::strip_ansi_codes(&format_job_diff("x", &changes))
+        );
+    }
+
+    #[test]
+    fn it_record_line() {
+        assert_eq!(
+            "ID          JobName           JobId             Status    StartAt              EndAt                Result",
+            record_line(&RECORD_HEADER)
+        );
+        let record: Record = serde_json::from_value(json!({
+            "id": 516544388, "job_id": "b1638cfb7a8d4247", "job_name": "myJob5",
+            "status": "running", "result": "",
+        }))
+        .unwrap();
+        assert_eq!(
+            "516544388   myJob5            b1638cfb7a8d4247  running",
+            record_line(&record_row(&record))
+        );
+        assert_eq!("Total 3 records", records_total(3, 3));
+        assert_eq!("Total 1 of 3 records", records_total(1, 3));
+    }
+
+    #[test]
+    fn it_jobs_footer() {
+        assert_eq!("Total 3", jobs_footer(3, 3));
+        assert_eq!("Total 1 of 3", jobs_footer(1, 3));
+    }
+
+    #[test]
+    fn it_job_choices() {
+        let jobs: Vec<Job> = serde_json::from_value(json!([
+            {"id": "00227fbf671f4ed2", "name": "myJob", "type": "cron", "func_name": "a.Print", "status": "paused"},
+            {"id": "8088b567cc3a4345", "name": "job2", "type": "interval", "func_name": "b.Sum", "status": "running"},
+        ]))
+        .unwrap();
+        assert_eq!(
+            vec![
+                "myJob  cron      a.Print  paused   00227fbf671f4ed2",
+                "job2   interval  b.Sum    running  8088b567cc3a4345",
+            ],
+            job_choices(&jobs)
+        );
+    }
+
+    #[test]
+    fn it_tables() {
+        let job: Job = serde_json::from_value(json!({
+            "id": "00227fbf671f4ed2",
+            "name": "myJob",
+            "type": "cron",
+            "cron_expr": "*/1 * * * *",
+            "last_run_time": null,
+            "status": "paused"
+        }))
+        .unwrap();
+        let table = jobs_table(&[job]).to_string();
+        assert!(table.contains("*/1 * * * *"));
+        assert!(table.contains("paused"));
+
+        let records: RecordPage = serde_json::from_value(json!({
+            "page": 1,
+            "page_size": 10,
+            "total": 1,
+            "res": [{"id": 516544388, "job_name": "myJob5", "status": "running", "end_at": null}]
+        }))
+        .unwrap();
+        assert!(records_table(&records).to_string().contains("516544388"));
+
+        let queue = Queue {
+            name: "default".to_string(),
+            workers: 2,
+            ..Default::default()
+        };
+        assert!(queues_table(&[queue]).to_string().contains("default"));
+
+        let node = ClusterNode {
+            endpoint: "127.0.0.1:36380".to_string(),
+            endpoint_main: "127.0.0.1:36380".to_string(),
+            ..Default::default()
+        };
+        assert!(cluster_nodes_table(&[node]).to_string().contains("true"));
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::output::OutputFormat;
+
+/// Connection settings of one scheduler, every unset field falls back to the command line defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub endpoint: Option<String>,
+    /// Stored in plain text, prefer `password_env`.
+    pub password: Option<String>,
+    /// Name of the environment variable holding the password.
+    pub password_env: Option<String>,
+    /// Request timeout in seconds.
+    pub timeout: Option<u64>,
+    pub http2: Option<bool>,
+    pub insecure: Option<bool>,
+    pub ca_cert: Option<PathBuf>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    pub output: Option<OutputFormat>,
+}
+
+impl Profile {
+    /// Returns the password of the profile, `password_env` takes precedence over `password`.
+    pub fn password(&self) -> anyhow::Result<Option<String>> {
+        if let Some(name) = &self.password_env {
+            return match env::var(name) {
+                Ok(password) => Ok(Some(password)),
+                Err(_) => Err(anyhow::anyhow!(
+                    "Environment variable `{name}` of the profile is not set"
+                )),
+            };
+        }
+
+        Ok(self.password.clone())
+    }
+}
+
+/// Contents of `config.toml`, holding named profiles and the one used by default.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigFile {
+    pub current: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl ConfigFile {
+    /// Returns `AGSCHEDULERCLI_CONFIG`, or `~/.config/agscheduler-cli/config.toml`.
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        if let Ok(path) = env::var("AGSCHEDULERCLI_CONFIG") {
+            return Ok(PathBuf::from(path));
+        }
+
+        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Home directory not found"))?;
+
+        Ok(home
+            .join(".config")
+            .join("agscheduler-cli")
+            .join("config.toml"))
+    }
+
+    /// Loads the config file, a missing file is an empty config.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(ConfigFile::default());
+        }
+
+        let text = fs::read_to_string(path)?;
+        toml::from_str(&text).map_err(|err| anyhow::anyhow!("Invalid `{}`: {err}", path.display()))
+    }
+
+    /// Writes the config file, readable only by the owner as it may hold passwords.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the named profile, or the current one, or an empty profile if none is set.
+    pub fn profile(&self, name: Option<&str>) -> anyhow::Result<Profile> {
+        match name.or(self.current.as_deref()) {
+            Some(name) => self
+                .profiles
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Profile `{name}` not found")),
+            None => Ok(Profile::default()),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> anyhow::Result<()> {
+        if self.profiles.remove(name).is_none() {
+            return Err(anyhow::anyhow!("Profile `{name}` not found"));
+        }
+        if self.current.as_deref() == Some(name) {
+            self.current = None;
+        }
+
+        Ok(())
+    }
+
+    pub fn use_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        if !self.profiles.contains_key(name) {
+            return Err(anyhow::anyhow!("Profile `{name}` not found"));
+        }
+        self.current = Some(name.to_string());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_config_file() {
+        let text = r#"
+current = "dev"
+
+[profiles.dev]
+endpoint = "http://127.0.0.1:36370"
+
+[profiles.prod]
+endpoint = "https://scheduler.example.com"
+password_env = "AGSCHEDULERCLI_TEST_UNSET_PASSWORD"
+timeout = 30
+output = "json"
+
+[profiles.prod.headers]
+X-Request-Source = "ci"
+"#;
+        let mut file: ConfigFile = toml::from_str(text).unwrap();
+
+        assert_eq!(
+            Some("http://127.0.0.1:36370".to_string()),
+            file.profile(None).unwrap().endpoint
+        );
+        let prod = file.profile(Some("prod")).unwrap();
+        assert_eq!(Some(30), prod.timeout);
+        assert_eq!(Some(OutputFormat::Json), prod.output);
+        assert_eq!("ci", prod.headers["X-Request-Source"]);
+        assert!(prod.password().is_err());
+        assert!(file.profile(Some("staging")).is_err());
+
+        file.use_profile("prod").unwrap();
+        assert_eq!(Some("prod".to_string()), file.current);
+        assert!(file.use_profile("staging").is_err());
+        file.remove("prod").unwrap();
+        assert_eq!(None, file.current);
+        assert_eq!(Profile::default(), file.profile(None).unwrap());
+        assert!(file.remove("prod").is_err());
+    }
+
+    #[test]
+    fn it_config_file_save_and_load() {
+        let path = env::temp_dir()
+            .join(format!("agscheduler-cli-{}", std::process::id()))
+            .join("config.toml");
+        assert_eq!(ConfigFile::default(), ConfigFile::load(&path).unwrap());
+
+        let mut file = ConfigFile::default();
+        file.profiles.insert(
+            "dev".to_string(),
+            Profile {
+                endpoint: Some("http://127.0.0.1:36370".to_string()),
+                password: Some("123".to_string()),
+                http2: Some(true),
+                ..Default::default()
+            },
+        );
+        file.use_profile("dev").unwrap();
+        file.save(&path).unwrap();
+
+        let loaded = ConfigFile::load(&path).unwrap();
+        assert_eq!(file, loaded);
+        assert_eq!(
+            Some("123".to_string()),
+            loaded.profile(None).unwrap().password().unwrap()
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
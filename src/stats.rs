@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::datetime;
+use crate::models::Record;
+
+/// Run statistics of a job, rates are over finished runs and durations in seconds.
+///
+/// The success rate counts the runs `completed` by the scheduler.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct JobStats {
+    pub job_id: String,
+    pub job_name: String,
+    pub runs: usize,
+    pub running: usize,
+    pub success_rate: f64,
+    pub error_rate: f64,
+    pub timeout_rate: f64,
+    pub min_secs: Option<f64>,
+    pub avg_secs: Option<f64>,
+    pub p95_secs: Option<f64>,
+    pub max_secs: Option<f64>,
+    pub last_failure: Option<String>,
+}
+
+/// Duration of a finished record.
+fn elapsed(record: &Record) -> Option<Duration> {
+    if record.status == "running" {
+        return None;
+    }
+    let start = datetime::parse_utc(record.start_at.as_deref())?;
+    let end = datetime::parse_utc(record.end_at.as_deref())?;
+
+    (end - start).to_std().ok()
+}
+
+fn stats(records: &[&Record]) -> JobStats {
+    let count = |status: &str| records.iter().filter(|r| r.status == status).count();
+    let running = count("running");
+    let finished = records.len() - running;
+    let rate = |n: usize| match finished {
+        0 => 0.0,
+        _ => n as f64 / finished as f64,
+    };
+
+    let mut durations: Vec<f64> = records
+        .iter()
+        .filter_map(|r| elapsed(r))
+        .map(|d| d.as_secs_f64())
+        .collect();
+    durations.sort_by(f64::total_cmp);
+    // Nearest-rank percentile.
+    let p95 = match durations.len() {
+        0 => None,
+        n => Some(durations[(n as f64 * 0.95).ceil() as usize - 1]),
+    };
+
+    let last_failure = records
+        .iter()
+        .filter(|r| r.status == "error" || r.status == "timeout")
+        .filter_map(|r| {
+            datetime::parse_utc(r.end_at.as_deref()).or(datetime::parse_utc(r.start_at.as_deref()))
+        })
+        .max()
+        .map(|t| t.to_rfc3339());
+
+    JobStats {
+        job_id: records[0].job_id.to_string(),
+        job_name: records[0].job_name.to_string(),
+        runs: records.len(),
+        running,
+        success_rate: rate(count("completed")),
+        error_rate: rate(count("error")),
+        timeout_rate: rate(count("timeout")),
+        min_secs: durations.first().copied(),
+        avg_secs: match durations.len() {
+            0 => None,
+            n => Some(durations.iter().sum::<f64>() / n as f64),
+        },
+        p95_secs: p95,
+        max_secs: durations.last().copied(),
+        last_failure,
+    }
+}
+
+/// Aggregates records per job, ordered by job name.
+pub fn aggregate(records: &[Record]) -> Vec<JobStats> {
+    let mut by_job: BTreeMap<&str, Vec<&Record>> = BTreeMap::new();
+    for r in records {
+        by_job.entry(&r.job_id).or_default().push(r);
+    }
+
+    let mut stats: Vec<JobStats> = by_job.values().map(|records| stats(records)).collect();
+    stats.sort_by(|a, b| a.job_name.cmp(&b.job_name).then(a.job_id.cmp(&b.job_id)));
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_aggregate() {
+        let mut records = vec![];
+        for (id, status, secs) in [
+            (1, "completed", 1),
+            (2, "completed", 3),
+            (3, "error", 2),
+            (4, "timeout", 10),
+            (5, "running", 0),
+        ] {
+            records.push(json!({
+                "id": id,
+                "job_id": "a1",
+                "job_name": "report",
+                "status": status,
+                "start_at": format!("2024-04-16T0{id}:00:00Z"),
+                "end_at": (status != "running").then(|| format!("2024-04-16T0{id}:00:{secs:02}Z")),
+            }));
+        }
+        records.push(json!({
+            "id": 6,
+            "job_id": "b2",
+            "job_name": "cleanup",
+            "status": "completed",
+            "start_at": "2024-04-16T06:00:00Z",
+            "end_at": "2024-04-16T06:00:00.5Z",
+        }));
+        let records: Vec<Record> = serde_json::from_value(json!(records)).unwrap();
+
+        let stats = aggregate(&records);
+        assert_eq!(
+            vec![
+                JobStats {
+                    job_id: "b2".to_string(),
+                    job_name: "cleanup".to_string(),
+                    runs: 1,
+                    running: 0,
+                    success_rate: 1.0,
+                    error_rate: 0.0,
+                    timeout_rate: 0.0,
+                    min_secs: Some(0.5),
+                    avg_secs: Some(0.5),
+                    p95_secs: Some(0.5),
+                    max_secs: Some(0.5),
+                    last_failure: None,
+                },
+                JobStats {
+                    job_id: "a1".to_string(),
+                    job_name: "report".to_string(),
+                    runs: 5,
+                    running: 1,
+                    success_rate: 0.5,
+                    error_rate: 0.25,
+                    timeout_rate: 0.25,
+                    min_secs: Some(1.0),
+                    avg_secs: Some(4.0),
+                    p95_secs: Some(10.0),
+                    max_secs: Some(10.0),
+                    last_failure: Some("2024-04-16T04:00:10+00:00".to_string()),
+                },
+            ],
+            stats
+        );
+        assert!(aggregate(&[]).is_empty());
+    }
+}
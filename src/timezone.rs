@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+use serde::Serialize;
+
+/// Minimum normalized Damerau-Levenshtein similarity of a suggested timezone.
+const SUGGEST_SIMILARITY: f64 = 0.8;
+
+/// An IANA timezone with its UTC offset at a given time.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Zone {
+    pub name: String,
+    pub offset: String,
+}
+
+/// Parses an IANA timezone name, suggesting the closest names on a typo.
+pub fn parse(name: &str) -> anyhow::Result<Tz> {
+    name.parse().map_err(|_| {
+        let suggestions = suggest(name, 3);
+        if suggestions.is_empty() {
+            return anyhow::anyhow!("Unknown timezone `{name}`");
+        }
+
+        let suggestions: Vec<String> = suggestions.iter().map(|s| format!("`{s}`")).collect();
+        anyhow::anyhow!(
+            "Unknown timezone `{name}`, did you mean {}?",
+            suggestions.join(" or ")
+        )
+    })
+}
+
+fn suggest(name: &str, count: usize) -> Vec<&'static str> {
+    let name = name.to_lowercase();
+    let mut scored: Vec<(f64, &'static str)> = TZ_VARIANTS
+        .iter()
+        .map(|tz| {
+            (
+                strsim::normalized_damerau_levenshtein(&name, &tz.name().to_lowercase()),
+                tz.name(),
+            )
+        })
+        .filter(|(score, _)| *score >= SUGGEST_SIMILARITY)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    scored.into_iter().take(count).map(|(_, n)| n).collect()
+}
+
+/// Formats the UTC offset of `tz` at `now`, like `UTC+08:00`.
+pub fn utc_offset(tz: Tz, now: DateTime<Utc>) -> String {
+    format!("UTC{}", now.with_timezone(&tz).format("%:z"))
+}
+
+/// Lists the timezones whose name contains `filter`, ignoring case.
+pub fn zones(filter: Option<&str>, now: DateTime<Utc>) -> Vec<Zone> {
+    let filter = filter.unwrap_or_default().to_lowercase();
+    let mut zones: Vec<Zone> = TZ_VARIANTS
+        .iter()
+        .filter(|tz| tz.name().to_lowercase().contains(&filter))
+        .map(|tz| Zone {
+            name: tz.name().to_string(),
+            offset: utc_offset(*tz, now),
+        })
+        .collect();
+    zones.sort_by(|a, b| a.name.cmp(&b.name));
+
+    zones
+}
+
+/// Lists the zones offered when editing a job whose timezone is `current`, with the one to preselect.
+///
+/// An unknown `current` is offered first so that it is kept by default,
+/// an empty one preselects `local` if known, and UTC otherwise.
+pub fn choices(current: &str, local: &str, now: DateTime<Utc>) -> (Vec<Zone>, usize) {
+    let mut zones = zones(None, now);
+    let position = |zones: &[Zone], name: &str| zones.iter().position(|z| z.name == name);
+
+    if current.is_empty() {
+        let default = position(&zones, local)
+            .or_else(|| position(&zones, "UTC"))
+            .unwrap_or_default();
+        return (zones, default);
+    }
+    if let Some(default) = position(&zones, current) {
+        return (zones, default);
+    }
+
+    zones.insert(
+        0,
+        Zone {
+            name: current.to_string(),
+            offset: "unknown offset".to_string(),
+        },
+    );
+    (zones, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parse() {
+        assert_eq!(Tz::Asia__Shanghai, parse("Asia/Shanghai").unwrap());
+        assert_eq!(
+            "Unknown timezone `Asia/Shangai`, did you mean `Asia/Shanghai`?",
+            parse("Asia/Shangai").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unknown timezone `utc`, did you mean `UTC`?",
+            parse("utc").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unknown timezone `nowhere`",
+            parse("nowhere").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn it_zones() {
+        let now = DateTime::parse_from_rfc3339("2024-01-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            vec![Zone {
+                name: "Asia/Shanghai".to_string(),
+                offset: "UTC+08:00".to_string(),
+            }],
+            zones(Some("shanghai"), now)
+        );
+        assert_eq!("UTC-05:00", utc_offset(Tz::America__New_York, now));
+        assert_eq!(TZ_VARIANTS.len(), zones(None, now).len());
+    }
+
+    #[test]
+    fn it_choices() {
+        let now = Utc::now();
+        let name = |(zones, default): (Vec<Zone>, usize)| zones[default].name.to_string();
+
+        assert_eq!("Asia/Shanghai", name(choices("Asia/Shanghai", "UTC", now)));
+        assert_eq!("Europe/Paris", name(choices("", "Europe/Paris", now)));
+        assert_eq!("UTC", name(choices("", "Somewhere/Local", now)));
+
+        let (zones, default) = choices("Mars/Olympus", "UTC", now);
+        assert_eq!((0, "Mars/Olympus"), (default, zones[0].name.as_str()));
+        assert_eq!(TZ_VARIANTS.len() + 1, zones.len());
+    }
+}
@@ -0,0 +1,210 @@
+use chrono::{DateTime, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::{Map, Value};
+
+use crate::cron::CronExpr;
+use crate::duration;
+use crate::models::Job;
+use crate::timezone;
+
+/// Format of `start_at`, in the timezone of the job.
+pub const START_AT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub fn cron_expr(text: &str) -> anyhow::Result<()> {
+    CronExpr::parse(text)
+        .map(|_| ())
+        .map_err(|err| anyhow::anyhow!("Invalid cron expression `{text}`: {err}"))
+}
+
+/// Accepts a Go duration greater than zero, like `60s` or `1h30m`.
+pub fn interval(text: &str) -> anyhow::Result<()> {
+    if duration::parse(text)
+        .map_err(|err| anyhow::anyhow!("Interval: {err}"))?
+        .is_zero()
+    {
+        return Err(anyhow::anyhow!("Interval must be greater than 0"));
+    }
+
+    Ok(())
+}
+
+/// Accepts a Go duration, like `1h` or `30s`.
+pub fn timeout(text: &str) -> anyhow::Result<()> {
+    duration::parse(text)
+        .map(|_| ())
+        .map_err(|err| anyhow::anyhow!("Timeout: {err}"))
+}
+
+/// Parses a start time like `2024-04-16 15:23:51`.
+pub fn start_at(text: &str) -> anyhow::Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(text.trim(), START_AT_FORMAT).map_err(|err| {
+        anyhow::anyhow!("Invalid start time `{text}`, expected `YYYY-MM-DD HH:MM:SS`: {err}")
+    })
+}
+
+/// Parses the args of a job, which must be a JSON object.
+pub fn args(text: &str) -> anyhow::Result<Map<String, Value>> {
+    match serde_json::from_str(text).map_err(|err| anyhow::anyhow!("Invalid JSON: {err}"))? {
+        Value::Object(map) => Ok(map),
+        _ => Err(anyhow::anyhow!(
+            r#"Args must be a JSON object, like {{"key": "value"}}"#
+        )),
+    }
+}
+
+/// Parses the queues of a job, which must be a JSON array of strings.
+pub fn queues(text: &str) -> anyhow::Result<Vec<String>> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|err| anyhow::anyhow!("Invalid JSON: {err}"))?;
+    serde_json::from_value(value)
+        .map_err(|_| anyhow::anyhow!(r#"Queues must be a JSON array of strings, like ["default"]"#))
+}
+
+/// Checks the fields of a job locally, before the scheduler rejects them or the job misfires.
+pub fn job(job: &Job) -> anyhow::Result<()> {
+    match job.job_type.as_str() {
+        "datetime" => {
+            start_at(&job.start_at)?;
+        }
+        "interval" => interval(&job.interval)?,
+        "cron" => cron_expr(&job.cron_expr)?,
+        _ => {}
+    }
+    if !job.timezone.is_empty() {
+        timezone::parse(&job.timezone)?;
+    }
+    if !job.timeout.is_empty() {
+        timeout(&job.timeout)?;
+    }
+
+    Ok(())
+}
+
+/// Returns warnings about a valid job that will not behave as probably intended.
+pub fn warnings(job: &Job, now: DateTime<Utc>) -> Vec<String> {
+    let mut warnings = vec![];
+
+    if job.job_type == "datetime" {
+        let tz = job.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+        if let Ok(naive) = start_at(&job.start_at) {
+            let start = match tz.from_local_datetime(&naive) {
+                LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => Some(t),
+                LocalResult::None => None,
+            };
+            if start.is_some_and(|t| t < now) {
+                warnings.push(format!(
+                    "Start time `{}` ({}) is already in the past",
+                    job.start_at,
+                    tz.name()
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_job() {
+        let mut cron = Job {
+            job_type: "cron".to_string(),
+            cron_expr: "*/5 * * * *".to_string(),
+            ..Default::default()
+        };
+        assert!(job(&cron).is_ok());
+
+        cron.cron_expr = "*/5 * * *".to_string();
+        assert_eq!(
+            "Invalid cron expression `*/5 * * *`: Expected 5 fields (minute hour day-of-month month day-of-week) or 6 with leading seconds, found 4",
+            job(&cron).unwrap_err().to_string()
+        );
+
+        let interval = Job {
+            job_type: "interval".to_string(),
+            interval: "60s".to_string(),
+            timeout: "1h".to_string(),
+            ..Default::default()
+        };
+        assert!(job(&interval).is_ok());
+        for (field, value, err) in [
+            ("interval", "0s", "Interval must be greater than 0"),
+            (
+                "interval",
+                "60",
+                "Interval: Invalid duration `60`, missing unit after `60` (ns, us, ms, s, m, h)",
+            ),
+            (
+                "timeout",
+                "1 hour",
+                "Timeout: Invalid duration `1 hour`, unknown unit ` hour` (ns, us, ms, s, m, h)",
+            ),
+        ] {
+            let mut invalid = interval.clone();
+            match field {
+                "interval" => invalid.interval = value.to_string(),
+                _ => invalid.timeout = value.to_string(),
+            }
+            assert_eq!(err, job(&invalid).unwrap_err().to_string());
+        }
+
+        let datetime = Job {
+            job_type: "datetime".to_string(),
+            start_at: "2024-04-16 15:23:51".to_string(),
+            ..Default::default()
+        };
+        assert!(job(&datetime).is_ok());
+        assert!(start_at("2024-04-16T15:23:51").is_err());
+        assert!(start_at("2024-02-30 15:23:51").is_err());
+    }
+
+    #[test]
+    fn it_args_and_queues() {
+        assert_eq!(json!({"a": 1}), Value::Object(args(r#"{"a": 1}"#).unwrap()));
+        assert_eq!(
+            "Invalid JSON: expected `:` at line 1 column 6",
+            args(r#"{"a" 1}"#).unwrap_err().to_string()
+        );
+        assert_eq!(
+            r#"Args must be a JSON object, like {"key": "value"}"#,
+            args("[1]").unwrap_err().to_string()
+        );
+
+        assert_eq!(vec!["default"], queues(r#"["default"]"#).unwrap());
+        assert_eq!(
+            "Invalid JSON: EOF while parsing a list at line 1 column 1",
+            queues("[").unwrap_err().to_string()
+        );
+        for text in ["{}", "[1]", r#""default""#] {
+            assert_eq!(
+                r#"Queues must be a JSON array of strings, like ["default"]"#,
+                queues(text).unwrap_err().to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn it_warnings() {
+        let now = DateTime::parse_from_rfc3339("2024-04-16T08:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut job = Job {
+            job_type: "datetime".to_string(),
+            start_at: "2024-04-16 15:23:51".to_string(),
+            timezone: "Asia/Shanghai".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["Start time `2024-04-16 15:23:51` (Asia/Shanghai) is already in the past"],
+            warnings(&job, now)
+        );
+
+        job.timezone = "UTC".to_string();
+        assert!(warnings(&job, now).is_empty());
+    }
+}
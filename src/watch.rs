@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use dialoguer::console::Term;
+
+/// Cells of each row, keyed by the first cell.
+type Rows = HashMap<String, Vec<String>>;
+
+fn cells(table: &Table) -> Vec<Vec<String>> {
+    table
+        .row_iter()
+        .map(|row| row.cell_iter().map(|c| c.content()).collect())
+        .collect()
+}
+
+/// Flags the rows that are new or differ from the previous frame, none on the first frame.
+fn changed(rows: &[Vec<String>], previous: Option<&Rows>) -> Vec<bool> {
+    let Some(previous) = previous else {
+        return vec![false; rows.len()];
+    };
+
+    rows.iter()
+        .map(|row| {
+            let key = row.first().cloned().unwrap_or_default();
+            previous.get(&key) != Some(row)
+        })
+        .collect()
+}
+
+/// Rebuilds the table with the changed rows highlighted, returning the rows of this frame.
+fn highlight(table: &Table, previous: Option<&Rows>) -> (Table, Rows) {
+    let rows = cells(table);
+
+    let mut highlighted = Table::new();
+    highlighted.set_content_arrangement(ContentArrangement::Dynamic);
+    if let Some(header) = table.header() {
+        highlighted.set_header(header.cell_iter().map(|c| c.content()));
+    }
+    for (row, changed) in rows.iter().zip(changed(&rows, previous)) {
+        highlighted.add_row(row.iter().map(|c| {
+            let cell = Cell::new(c);
+            match changed {
+                true => cell.fg(Color::Yellow).add_attribute(Attribute::Bold),
+                false => cell,
+            }
+        }));
+    }
+
+    let rows = rows
+        .into_iter()
+        .map(|row| (row.first().cloned().unwrap_or_default(), row))
+        .collect();
+
+    (highlighted, rows)
+}
+
+/// Shows the cursor again when dropped, also on errors.
+struct CursorGuard(Term);
+
+impl CursorGuard {
+    fn hide(term: Term) -> anyhow::Result<Self> {
+        term.hide_cursor()?;
+
+        Ok(CursorGuard(term))
+    }
+}
+
+impl Drop for CursorGuard {
+    fn drop(&mut self) {
+        let _ = self.0.show_cursor();
+    }
+}
+
+/// Redraws the table and footer returned by `frame` every `interval` until Ctrl-C.
+///
+/// Errors of a single refresh are shown in place of the table and do not stop watching.
+pub async fn watch<F, Fut>(interval: Duration, title: &str, mut frame: F) -> anyhow::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<(Table, String)>>,
+{
+    let term = Term::stdout();
+    let mut previous: Option<Rows> = None;
+
+    let _guard = CursorGuard::hide(term.clone())?;
+    let result = loop {
+        let text = tokio::select! {
+            result = frame() => match result {
+                Ok((table, footer)) => {
+                    let (table, rows) = highlight(&table, previous.as_ref());
+                    previous = Some(rows);
+                    format!("{table}\n{footer}")
+                }
+                Err(err) => format!("Error: {err}"),
+            },
+            result = tokio::signal::ctrl_c() => break result,
+        };
+
+        term.clear_screen()?;
+        term.write_line(&format!(
+            "Every {}s: {}    {}\n\n{}",
+            interval.as_secs(),
+            title,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            text
+        ))?;
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            result = tokio::signal::ctrl_c() => break result,
+        }
+    };
+
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[[&str; 2]]) -> Table {
+        let mut table = Table::new();
+        table.set_header(vec!["ID", "Status"]);
+        for row in rows {
+            table.add_row(row.to_vec());
+        }
+        table
+    }
+
+    #[test]
+    fn it_highlight() {
+        let first = table(&[["1", "running"], ["2", "running"]]);
+        let (highlighted, rows) = highlight(&first, None);
+        assert_eq!(vec![false, false], changed(&cells(&first), None));
+        assert_eq!(first.to_string(), highlighted.to_string());
+
+        let second = table(&[["1", "running"], ["2", "paused"], ["3", "running"]]);
+        assert_eq!(
+            vec![false, true, true],
+            changed(&cells(&second), Some(&rows))
+        );
+        let (highlighted, rows) = highlight(&second, Some(&rows));
+        assert!(highlighted.to_string().contains("paused"));
+        assert_eq!(vec!["2", "paused"], rows["2"]);
+    }
+}